@@ -1,19 +1,74 @@
 use std::path::PathBuf;
 use thiserror::Error;
 
-use crate::{parser::ParseError, reader::ReadError};
+use crate::{parser::ParseError, reader::{Position, ReadError}};
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("IO error for file \"{0}\": {1}")]
     Io(PathBuf, std::io::Error),
-    #[error("reading error: {0}")]
-    Read(ReadError),
-    #[error("parsing error in file \"{0}\": {1}")]
-    Parse(PathBuf, ParseError),
+    #[error("reading error: {1}\n{0}")]
+    Read(Box<Location>, ReadError),
+    #[error("parsing error: {1}\n{0}")]
+    Parse(Box<Location>, ParseError),
 }
-impl From<ReadError> for Error {
-    fn from(value: ReadError) -> Self {
-        Self::Read(value)
+impl Error {
+    /// Describes the problem as a warning, for when it is allowed by a lint
+    /// level.
+    #[must_use]
+    pub fn warning(&self) -> String {
+        match self {
+            Self::Parse(location, error) => {
+                format!("warning: {error}\n{location}")
+            },
+            Self::Read(..) | Self::Io(..) => self.to_string(),
+        }
+    }
+}
+
+/// Where in which file something happened, along with the line it happened 
+/// on.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    /// The whole line the position is on.
+    pub snippet: String,
+}
+impl Location {
+    /// Finds the position in the source, and copies the line it is on.
+    #[must_use]
+    pub fn new(path: PathBuf, source: &str, position: Position) -> Self {
+        let offset = position.offset.min(source.len());
+        let start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let end = source[offset..].find('\n').map_or(source.len(), |i| i + offset);
+
+        Self {
+            path,
+            line: position.line,
+            column: position.column,
+            snippet: source[start..end].trim_end_matches('\r').to_string(),
+        }
+    }
+}
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self { path, line, column, snippet } = self;
+        writeln!(f, " --> {}:{line}:{column}", path.display())?;
+
+        if snippet.is_empty() { return Ok(()); }
+
+        // Tabs are kept so the caret lines up however they are displayed
+        let indent = snippet
+        .chars()
+        .take(column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+        let margin = " ".repeat(line.to_string().len());
+
+        writeln!(f, "{margin} |")?;
+        writeln!(f, "{line} | {snippet}")?;
+        write!(f, "{margin} | {indent}^")
     }
 }
@@ -1,13 +1,22 @@
 use proc_macro2::{Span, TokenStream};
 use quote::{ToTokens, quote};
 
-use crate::{LOCALE_FAILURE_MESSAGE, name::Name, parser::Key};
+use crate::{
+    name::Name, 
+    parser::{Direction, Key, Message, Part, Plural, Select}, 
+    plural::{self, Rule},
+};
 
 #[cfg(test)]
 mod tests;
 
 pub struct Generator {
+    /// The variant and code, as declared, of each locale.
     locales: Vec<(syn::Ident, String)>,
+    /// The name of each locale in its own language, if they were given.
+    native_names: Option<Vec<String>>,
+    /// The direction of each locale; any left out are left-to-right.
+    directions: Vec<Direction>,
     keys: Vec<Key>,
 }
 
@@ -19,16 +28,32 @@ impl Generator {
         .into_iter()
         .map(|loc| (
             syn::Ident::new(&loc.type_name(), Span::call_site()),
-            loc.into(), 
+            loc.as_written().to_owned(), 
         ))
         .collect();
 
         Self { 
             locales, 
+            native_names: None,
+            directions: Vec::new(),
             keys,
         }
     }
 
+    #[must_use]
+    /// Sets the name of each locale in its own language.
+    pub fn native_names(mut self, names: Option<Vec<String>>) -> Self {
+        self.native_names = names;
+        self
+    }
+
+    #[must_use]
+    /// Sets the direction of each locale.
+    pub fn directions(mut self, directions: Vec<Direction>) -> Self {
+        self.directions = directions;
+        self
+    }
+
     #[must_use]
     /// Generates code.
     /// 
@@ -36,8 +61,16 @@ impl Generator {
     /// If there are no defined locales.
     pub fn generate(mut self) -> TokenStream {
         let locales = self.generate_enum();
+        let conversions = self.generate_conversions();
+        let metadata = self.generate_metadata();
         let getter = Self::generate_getter();
         let setter = Self::generate_setter();
+        let overrides = Self::generate_overrides();
+        let negotiation = self.generate_negotiation();
+        let plurals = self.generate_plurals();
+        let selects = self.generate_selects();
+        
+        let display = self.generate_display();
         
         let keys = std::mem::take(&mut self.keys)
         .into_iter()
@@ -46,26 +79,42 @@ impl Generator {
 
         quote! {
             #locales
+            #conversions
+            #metadata
             #getter
             #setter
+            #overrides
+            #negotiation
+            #plurals
+            #selects
             #(#keys)*
+            #display
         }.into_token_stream()
     }
     
     /// Generates an enum of locales, and a static var to keep it.
     fn generate_enum(&self) -> TokenStream {
         let locales = self.locales.iter().map(|(i, _)| i).collect::<Vec<_>>();
-        let default = locales[0];
         let count = self.locales.len();
 
+        // The smallest representation that fits all locales
+        let (repr, atomic) = if count <= 1 << u8::BITS {
+            (quote!(u8), quote!(AtomicU8))
+        } else {
+            (quote!(u16), quote!(AtomicU16))
+        };
+
         let enum_comment = comment("The locales available.");
         let const_comment = comment("All locales, in the order they were \
             declared.");
-        let locale_comment = comment("The current locale.");
+        let locale_comment = comment("The current locale, as its index in \
+            [`LOCALES`]. It is private, so that only `set_locale` writes it and \
+            it is always a valid index.");
 
         quote! {
             #enum_comment
-            #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+            #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+            #[repr(#repr)]
             pub enum Locale {
                 #(#locales,)*
             }
@@ -76,26 +125,169 @@ impl Generator {
             ];
 
             #locale_comment
-            pub static LOCALE: std::sync::Mutex<Locale> = 
-                std::sync::Mutex::new(Locale::#default);
+            static LOCALE: std::sync::atomic::#atomic = 
+                std::sync::atomic::#atomic::new(0);
         }.into_token_stream()
     }
 
+    /// Generates conversions of locales to and from their codes and indices.
+    fn generate_conversions(&self) -> TokenStream {
+        let (locales, codes): (Vec<_>, Vec<_>) = self.locales
+        .iter()
+        .map(|(ident, code)| (ident, code))
+        .unzip();
+        let count = self.locales.len();
+        let first = locales[0];
+
+        let count_comment = comment("The number of locales.");
+        let code_comment = comment("\
+            Gives the code of the locale, as it was declared.");
+        let as_index_comment = comment("\
+            Gives the index of the locale in [`LOCALES`].");
+        let from_index_comment = comment("\
+            Gives the locale at an index in [`LOCALES`], if there is one.");
+        let error_comment = comment("\
+            The error of parsing a locale that was not declared.");
+        let default_comment = comment("The first declared locale.");
+        let from_str_comment = comment("\
+            Parses the code of a locale, ignoring case and whether subtags \
+            are separated by `-` or `_`.");
+
+        quote! {
+            impl Locale {
+                #count_comment
+                pub const COUNT: usize = #count;
+
+                #code_comment
+                #[must_use]
+                pub const fn code(self) -> &'static str {
+                    match self {
+                        #(Self::#locales => #codes,)*
+                    }
+                }
+
+                #as_index_comment
+                #[must_use]
+                pub const fn as_index(self) -> usize { self as usize }
+
+                #from_index_comment
+                #[must_use]
+                pub const fn from_index(index: usize) -> Option<Self> {
+                    if index < Self::COUNT { Some(LOCALES[index]) } else { None }
+                }
+            }
+
+            #default_comment
+            impl Default for Locale {
+                fn default() -> Self { Self::#first }
+            }
+
+            impl std::fmt::Display for Locale {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.pad(self.code())
+                }
+            }
+
+            #error_comment
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            pub struct UnknownLocale(pub String);
+            impl std::fmt::Display for UnknownLocale {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    write!(f, "unknown locale \"{}\"", self.0)
+                }
+            }
+            impl std::error::Error for UnknownLocale {}
+
+            #from_str_comment
+            impl std::str::FromStr for Locale {
+                type Err = UnknownLocale;
+
+                fn from_str(code: &str) -> Result<Self, Self::Err> {
+                    Self::lookup(code).ok_or_else(|| UnknownLocale(code.to_owned()))
+                }
+            }
+
+            #from_str_comment
+            impl TryFrom<&str> for Locale {
+                type Error = UnknownLocale;
+
+                fn try_from(code: &str) -> Result<Self, Self::Error> {
+                    code.parse()
+                }
+            }
+        }
+    }
+
+    /// Generates the direction of every locale, and its native name if they 
+    /// were given.
+    fn generate_metadata(&self) -> TokenStream {
+        let locales = self.locales.iter().map(|(i, _)| i).collect::<Vec<_>>();
+        let directions = (0..locales.len()).map(|i| 
+            match self.directions.get(i).copied().unwrap_or_default() {
+                Direction::Ltr => quote!(Direction::Ltr),
+                Direction::Rtl => quote!(Direction::Rtl),
+            }
+        );
+
+        let direction_comment = comment("Which way text is written.");
+        let ltr_comment = comment("Left to right.");
+        let rtl_comment = comment("Right to left.");
+        let locale_direction_comment = comment("\
+            Gives the direction the locale is written in.");
+
+        let native_name = self.native_names.as_ref().map(|names| {
+            let comment = comment("\
+                Gives the name of the locale in its own language.");
+
+            quote! {
+                #comment
+                #[must_use]
+                pub const fn native_name(self) -> &'static str {
+                    match self {
+                        #(Self::#locales => #names,)*
+                    }
+                }
+            }
+        });
+
+        quote! {
+            #direction_comment
+            #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+            pub enum Direction {
+                #ltr_comment
+                Ltr,
+                #rtl_comment
+                Rtl,
+            }
+
+            impl Locale {
+                #locale_direction_comment
+                #[must_use]
+                pub const fn direction(self) -> Direction {
+                    match self {
+                        #(Self::#locales => #directions,)*
+                    }
+                }
+
+                #native_name
+            }
+        }
+    }
+
     /// Generates a function to get the current locale.
     fn generate_getter() -> TokenStream {
         let comment = comment("\
-            Returns the current locale. As this calls `Mutex::lock()`, it \
-            will block the thread until it is safe to access. \n\n\
-            # Panic \n\
-            It will panic if the `Mutex` has been poisoned. See \
-            [`std::sync::Mutex`].");
+            Returns the current locale: the one set for this thread, if any, \
+            or else the global one. Neither blocks.");
 
         quote! {
             #comment
             pub fn get_locale() -> Locale {
-                *LOCALE
-                .lock()
-                .expect(#LOCALE_FAILURE_MESSAGE)
+                THREAD_LOCALE
+                .with(std::cell::Cell::get)
+                .unwrap_or_else(|| 
+                    LOCALES[LOCALE.load(std::sync::atomic::Ordering::Relaxed) as usize]
+                )
             }
         }
     }
@@ -103,53 +295,319 @@ impl Generator {
     /// Generates a function to set the current locale.
     fn generate_setter() -> TokenStream {
         let comment = comment("\
-            Sets the current locale. As this calls `Mutex::lock()`, it will \
-            block the thread until it is safe to access. \n\n\
-            # Panic \n\
-            It will panic if the `Mutex` has been poisoned. See \
-            [`std::sync::Mutex`].");
+            Sets the global locale, used by any thread without one of its \
+            own. It is kept in an atomic, so this never blocks.");
 
         quote! {
             #comment
             pub fn set_locale(locale: Locale) {
-                *LOCALE
-                .lock()
-                .expect(#LOCALE_FAILURE_MESSAGE)
-                    = locale;
+                LOCALE.store(
+                    locale as _, 
+                    std::sync::atomic::Ordering::Relaxed,
+                );
             }
         }.into_token_stream()
     }
 
-    fn generate_from_key(&self, key: Key) -> TokenStream {
-        let Key { id, arguments, comment, entries } = key;
+    /// Generates a thread-local locale, which overrides the global one, and
+    /// the means to set it for a scope.
+    fn generate_overrides() -> TokenStream {
+        let guard_comment = comment("\
+            Overrides the locale of the current thread until dropped, then \
+            restores whatever it was before.");
+        let override_comment = comment("\
+            Overrides the locale of the current thread, until the returned \
+            guard is dropped. Other threads are not affected.");
+        let with_comment = comment("\
+            Calls `f` with the locale of the current thread overridden. Other \
+            threads are not affected.");
 
-        // All go to params, but only positinal go to arguments
-        let (positional, named): (Vec<_>, Vec<_>) = arguments
-        .into_iter()
-        .partition(|a| a.chars().all(char::is_numeric));
+        quote! {
+            std::thread_local! {
+                static THREAD_LOCALE: std::cell::Cell<Option<Locale>> = 
+                    const { std::cell::Cell::new(None) };
+            }
 
-        let named = named
-        .into_iter()
-        .map(|a| syn::Ident::new(&a, Span::call_site()));
+            #guard_comment
+            pub struct LocaleGuard {
+                previous: Option<Locale>,
+                // The override belongs to the thread, so the guard must too
+                thread: std::marker::PhantomData<*const ()>,
+            }
+            impl Drop for LocaleGuard {
+                fn drop(&mut self) {
+                    THREAD_LOCALE.set(self.previous);
+                }
+            }
 
-        let positional = positional
-        .into_iter()
-        .map(|i| format!("arg{i}"))
-        .map(|a| syn::Ident::new(&a, Span::call_site()));
+            #override_comment
+            #[must_use = "the override ends when the guard is dropped"]
+            pub fn override_locale(locale: Locale) -> LocaleGuard {
+                LocaleGuard {
+                    previous: THREAD_LOCALE.replace(Some(locale)),
+                    thread: std::marker::PhantomData,
+                }
+            }
+
+            #with_comment
+            pub fn with_locale<R>(locale: Locale, f: impl FnOnce() -> R) -> R {
+                let _guard = override_locale(locale);
+                f()
+            }
+        }
+    }
+
+    /// Generates functions to find the locale best matching a list of 
+    /// language tags, such as from `Accept-Language` or `LANG`.
+    fn generate_negotiation(&self) -> TokenStream {
+        let (locales, tags): (Vec<_>, Vec<_>) = self.locales
+        .iter()
+        .map(|(ident, code)| (ident, code.to_ascii_lowercase().replace('_', "-")))
+        .unzip();
+
+        let lookup_comment = comment("\
+            Finds the locale declared with the given tag, ignoring case and \
+            whether subtags are separated by `-` or `_`.");
+        let negotiate_comment = comment("\
+            Picks the locale best matching a list of language tags, such as \
+            `[\"fr-CA\", \"fr;q=0.9\", \"en;q=0.5\"]` or a whole \
+            `Accept-Language` header. Tags are tried in order of quality, \
+            each with its subtags cut off one by one, so that `fr-CA` \
+            matches `fr`. POSIX locales like `de_DE.UTF-8` work as well. If \
+            nothing matches, the first declared locale is picked.");
+        let env_comment = comment("\
+            Negotiates the locale from the POSIX locale variables `LC_ALL`, \
+            `LC_MESSAGES`, and `LANG`, in that order of priority.");
+
+        quote! {
+            impl Locale {
+                #lookup_comment
+                fn lookup(tag: &str) -> Option<Self> {
+                    match tag.to_ascii_lowercase().replace('_', "-").as_str() {
+                        #(#tags => Some(Self::#locales),)*
+                        _ => None,
+                    }
+                }
+
+                #negotiate_comment
+                #[must_use]
+                pub fn negotiate(tags: &[&str]) -> Self {
+                    let mut ranges = tags
+                    .iter()
+                    .flat_map(|tags| tags.split(','))
+                    .filter_map(|range| {
+                        let mut parts = range.split(';');
+                        // Encodings and modifiers of POSIX locales are of 
+                        // no interest
+                        let tag = parts.next()?.trim().split(['.', '@']).next()?;
+                        let quality = parts
+                            .find_map(|part| part.trim().strip_prefix("q="))
+                            .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+
+                        (quality > 0.0 && !tag.is_empty())
+                        .then_some((tag, quality))
+                    })
+                    .collect::<Vec<_>>();
+
+                    // The sort is stable, so tags of equal quality keep 
+                    // their order
+                    ranges.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+                    ranges
+                    .into_iter()
+                    .find_map(|(mut tag, _)| loop {
+                        if let Some(locale) = Self::lookup(tag) { 
+                            return Some(locale); 
+                        }
+                        tag = &tag[..tag.rfind(['-', '_'])?];
+                    })
+                    .unwrap_or_default()
+                }
+
+                #env_comment
+                #[must_use]
+                pub fn from_env() -> Self {
+                    let vars = ["LC_ALL", "LC_MESSAGES", "LANG"]
+                    .into_iter()
+                    .filter_map(|var| std::env::var(var).ok())
+                    .collect::<Vec<_>>();
+
+                    Self::negotiate(
+                        &vars.iter().map(String::as_str).collect::<Vec<_>>()
+                    )
+                }
+            }
+        }
+    }
+
+    /// Generates a module with a type for every key, which formats the text 
+    /// only when displayed, and a function to create it. Keys without 
+    /// arguments need no such type, so their functions are just re-exported.
+    fn generate_display(&self) -> TokenStream {
+        let module_comment = comment("Texts that are formatted only when \
+            displayed, borrowing their arguments. Create them with the \
+            function of the same name as the key.");
+
+        let keys = self.keys.iter().map(|key| {
+            let Key { id, arguments, entries, inherited, fallbacks, .. } = key;
+            let type_name = syn::Ident::new(&id.type_name(), Span::call_site());
+            let id = syn::Ident::new(id.to_str(), Span::call_site());
+
+            if arguments.is_empty() {
+                return quote! { pub use super::#id; };
+            }
+
+            let params = parameters(arguments);
+            let generics = (0..params.len())
+            .map(|i| syn::Ident::new(&format!("T{i}"), Span::call_site()))
+            .collect::<Vec<_>>();
+
+            let bounds = bounds(&params, entries, &quote!(super::));
+
+            let entries = self
+            .patterns(inherited, &quote!(super::Locale))
+            .into_iter()
+            .map(|(pattern, i)| {
+                // An entry from a fallback keeps the plural rules it was 
+                // written for
+                let rules = fallbacks
+                .iter()
+                .find_map(|&(l, from)| (l == i).then_some(from))
+                .map_or_else(
+                    || quote!(*locale),
+                    |from| {
+                        let from = &self.locales[from].0;
+                        quote!(super::Locale::#from)
+                    },
+                );
+                let entry = lower(&entries[i], &rules);
+                quote! {
+                    #pattern => #entry
+                }
+            });
+
+            let type_comment = comment(&format!("The text of [`{id}`], \
+                formatted when displayed."));
+            let fn_comment = comment(&format!("Creates a [`{type_name}`], \
+                which formats the text of [`super::{id}`] when displayed."));
+
+            quote! {
+                #type_comment
+                pub struct #type_name<'a, #(#generics: ?Sized,)*> {
+                    pub locale: super::Locale,
+                    #(pub #params: &'a #generics,)*
+                }
+
+                impl<#(#generics: #bounds + ?Sized,)*> 
+                    std::fmt::Display for #type_name<'_, #(#generics,)*> 
+                {
+                    // Arguments cannot start with `_`, so none shadows the 
+                    // formatter
+                    fn fmt(
+                        &self, 
+                        __f: &mut std::fmt::Formatter<'_>,
+                    ) -> std::fmt::Result {
+                        let Self { locale, #(#params,)* } = self;
+                        match locale {
+                            #(#entries,)*
+                        }
+                    }
+                }
+
+                #fn_comment
+                pub const fn #id<'a, #(#generics: ?Sized,)*>(
+                    locale: super::Locale,
+                    #(#params: &'a #generics,)*
+                ) -> #type_name<'a, #(#generics,)*> {
+                    #type_name { locale, #(#params,)* }
+                }
+            }
+        });
 
-        let arguments = positional.clone().collect::<Vec<_>>();
-        let params = named.chain(positional);
+        quote! {
+            #module_comment
+            pub mod display {
+                #(#keys)*
+            }
+        }
+    }
+
+    /// Gives a pattern for each locale with an entry of its own, matching it 
+    /// along with the locales inheriting its entry, and the entry's index.
+    fn patterns(
+        &self, 
+        inherited: &[(usize, usize)], 
+        locale_type: &TokenStream,
+    ) -> Vec<(TokenStream, usize)> {
+        (0..self.locales.len())
+        .filter(|i| !inherited.iter().any(|(child, _)| child == i))
+        .map(|i| {
+            let locales = std::iter::once(i)
+            .chain(inherited
+                .iter()
+                .filter(|(_, from)| *from == i)
+                .map(|(child, _)| *child)
+            )
+            .map(|l| &self.locales[l].0);
+
+            (quote! { #(#locale_type::#locales)|* }, i)
+        })
+        .collect()
+    }
+
+    fn generate_from_key(&self, key: Key) -> TokenStream {
+        let Key { id, arguments, comment, entries, inherited, .. } = key;
 
         let id = syn::Ident::new(id.to_str(), Span::call_site());
         let comment = comment.map(|c| quote! {#[doc = #c]});
+        let patterns = self.patterns(&inherited, &quote!(Locale));
+
+        // Without arguments there is nothing to format, so the text can be
+        // returned as is
+        if arguments.is_empty() {
+            // Constructs need arguments, so these entries are only text
+            let entries = patterns
+            .into_iter()
+            .map(|(pattern, i)| {
+                let entry = unescape(entries[i].as_text().unwrap_or_default());
+                quote! {
+                    #pattern => #entry
+                }
+            });
+
+            return quote! {
+                #comment
+                pub fn #id(locale: Locale) -> &'static str {
+                    match locale {
+                        #(#entries,)*
+                    }
+                }
+            };
+        }
 
-        let entries = entries
+        let params = parameters(&arguments);
+
+        // Constructs are only lowered once, for the display type to use
+        if entries.iter().any(|e| e.as_text().is_none()) {
+            let types = bounds(&params, &entries, &quote!());
+
+            return quote! {
+                #comment
+                pub fn #id(
+                    locale: Locale,
+                    #(#params:impl #types,)*
+                ) -> String {
+                    display::#id(locale, #(&#params),*).to_string()
+                }
+            };
+        }
+
+        let entries = patterns
         .into_iter()
-        .enumerate()
-        .map(|(i, entry)| {
-            let locale = &self.locales[i].0;
+        .map(|(pattern, i)| {
+            let entry = entries[i].as_text().unwrap_or_default();
             quote! {
-                Locale::#locale => format!(#entry, #(#arguments,)*)
+                #pattern => format!(#entry)
             }
         });
 
@@ -165,6 +623,360 @@ impl Generator {
             }
         }
     }
+
+    /// Generates a module with the trait of values to select on, if any key 
+    /// uses selects.
+    fn generate_selects(&self) -> Option<TokenStream> {
+        let used = self.keys
+        .iter()
+        .flat_map(|key| &key.entries)
+        .any(|entry| !entry.selects().is_empty());
+        if !used { return None; }
+
+        let module_comment = comment("Values that pick a branch of a select.");
+        let trait_comment = comment("\
+            A value that picks a branch of a select, such as a gender. \
+            Implement it for an enum to select on that.");
+        let select_comment = comment("\
+            Gives the name of the branch to pick, e.g. `\"female\"`. A name \
+            without a branch of its own picks `other`.");
+
+        Some(quote! {
+            #module_comment
+            pub mod select {
+                #trait_comment
+                pub trait Select {
+                    #select_comment
+                    fn select(&self) -> &str;
+                }
+                impl Select for str {
+                    fn select(&self) -> &str { self }
+                }
+                impl Select for String {
+                    fn select(&self) -> &str { self }
+                }
+                impl<T: Select + ?Sized> Select for &T {
+                    fn select(&self) -> &str { (**self).select() }
+                }
+            }
+        })
+    }
+
+    /// Generates a module to pick the plural category of a count in each 
+    /// locale, if any key uses plurals.
+    fn generate_plurals(&self) -> Option<TokenStream> {
+        let plurals = self.keys
+        .iter()
+        .flat_map(|key| &key.entries)
+        .flat_map(Message::plurals)
+        .collect::<Vec<_>>();
+        if plurals.is_empty() { return None; }
+
+        let mut used_rules = Vec::new();
+        let cardinals = plurals.iter().any(|p| !p.ordinal).then(|| {
+            let arms = self.category_arms(plural::cardinal, &mut used_rules);
+            category_functions(false, &arms)
+        });
+        let ordinals = plurals.iter().any(|p| p.ordinal).then(|| {
+            let arms = self.category_arms(plural::ordinal, &mut used_rules);
+            category_functions(true, &arms)
+        });
+
+        let functions = used_rules.iter().map(|rule| {
+            let function = rule_function(*rule);
+            let (n, body) = rule.tokens();
+            quote! {
+                fn #function(#n: u64) -> Category { #body }
+            }
+        });
+
+        let module_comment = comment("Plural categories, and the rules \
+            picking them for each locale.");
+        let category_comment = comment("A CLDR plural category.");
+        let count_comment = comment("A count that picks a plural branch.");
+        let operand_comment = comment("\
+            Gives the count as a whole number, or `u64::MAX` if it is too \
+            large. Negative counts are picked by their absolute value.");
+
+        let unsigned = ["u8", "u16", "u32", "u64", "u128", "usize"]
+        .map(|t| syn::Ident::new(t, Span::call_site()));
+        let signed = ["i8", "i16", "i32", "i64", "i128", "isize"]
+        .map(|t| syn::Ident::new(t, Span::call_site()));
+
+        Some(quote! {
+            #module_comment
+            pub mod plural {
+                #category_comment
+                #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+                pub enum Category {
+                    Zero,
+                    One,
+                    Two,
+                    Few,
+                    Many,
+                    Other,
+                }
+
+                #count_comment
+                pub trait Count: std::fmt::Display {
+                    #operand_comment
+                    fn operand(&self) -> u64;
+                }
+                #(impl Count for #unsigned {
+                    fn operand(&self) -> u64 { 
+                        u64::try_from(*self).unwrap_or(u64::MAX) 
+                    }
+                })*
+                #(impl Count for #signed {
+                    fn operand(&self) -> u64 { 
+                        u64::try_from(self.unsigned_abs()).unwrap_or(u64::MAX) 
+                    }
+                })*
+                impl<T: Count + ?Sized> Count for &T {
+                    fn operand(&self) -> u64 { (**self).operand() }
+                }
+
+                #cardinals
+                #ordinals
+
+                #(#functions)*
+            }
+        })
+    }
+
+    /// Gives the arms matching each locale to the function of its rule, as 
+    /// found by `lookup`, and adds the rules to `used`.
+    fn category_arms(
+        &self, 
+        lookup: fn(&str) -> Option<Rule>,
+        used: &mut Vec<Rule>,
+    ) -> Vec<TokenStream> {
+        // Locales without rules cannot have plurals, so they need none
+        let rules = self.locales
+        .iter()
+        .map(|(_, code)| lookup(code).unwrap_or(Rule::None))
+        .collect::<Vec<_>>();
+        let mut distinct = Vec::new();
+        for rule in &rules {
+            if !distinct.contains(rule) { distinct.push(*rule); }
+            if !used.contains(rule) { used.push(*rule); }
+        }
+
+        distinct
+        .iter()
+        .map(|rule| {
+            let locales = self.locales
+            .iter()
+            .zip(&rules)
+            .filter(|(_, r)| *r == rule)
+            .map(|((ident, _), _)| ident);
+            let function = rule_function(*rule);
+
+            quote! { #(super::Locale::#locales)|* => #function(n) }
+        })
+        .collect()
+    }
+}
+
+/// Gives the functions picking the plural category of a count in a locale, 
+/// `category` and `select`, or `ordinal_category` and `select_ordinal`.
+fn category_functions(ordinal: bool, arms: &[TokenStream]) -> TokenStream {
+    let (category, select, kind) = if ordinal {
+        (quote!(ordinal_category), quote!(select_ordinal), "ordinal plural")
+    } else {
+        (quote!(category), quote!(select), "plural")
+    };
+
+    let category_comment = comment(&format!("\
+        Gives the {kind} category of a count in a locale."));
+    let select_comment = comment(&format!("\
+        Gives a count as a whole number, and its {kind} category in a \
+        locale."));
+
+    quote! {
+        #category_comment
+        #[must_use]
+        pub fn #category(locale: super::Locale, n: u64) -> Category {
+            match locale {
+                #(#arms,)*
+            }
+        }
+
+        #select_comment
+        pub fn #select<T: Count + ?Sized>(
+            locale: super::Locale, 
+            count: &T,
+        ) -> (u64, Category) {
+            let n = count.operand();
+            (n, #category(locale, n))
+        }
+    }
+}
+
+/// Lowers a message to what writes it to `__f`, in the `fmt` of a display
+/// type. Its plurals pick their branch by the rules of `rules`, the locale
+/// the message was written for.
+fn lower(message: &Message, rules: &TokenStream) -> TokenStream {
+    if let Some(text) = message.as_text() {
+        return quote! { write!(__f, #text) };
+    }
+
+    let statements = lower_parts(message, rules);
+    quote! {{
+        #statements
+        Ok(())
+    }}
+}
+
+/// Lowers the parts of a message to statements writing each of them.
+fn lower_parts(message: &Message, rules: &TokenStream) -> TokenStream {
+    let parts = message.0.iter().map(|part| match part {
+        Part::Text(text) => quote! { write!(__f, #text)?; },
+        Part::Plural(plural) => lower_plural(plural, rules),
+        Part::Select(select) => lower_select(select, rules),
+    });
+
+    quote! { #(#parts)* }
+}
+
+fn lower_plural(plural: &Plural, rules: &TokenStream) -> TokenStream {
+    let Plural { argument, ordinal, exact, categories } = plural;
+    let count = parameter(argument);
+    let select = if *ordinal { quote!(select_ordinal) } else { quote!(select) };
+
+    let exact = exact.iter().map(|(n, message)| {
+        let message = lower_parts(message, rules);
+        quote! { (#n, _) => { #message } }
+    });
+
+    // Any category left out falls to `other`, which is always given
+    let (other, categories): (Vec<_>, Vec<_>) = categories
+    .iter()
+    .partition(|(c, _)| *c == plural::Category::Other);
+    let categories = categories.into_iter().map(|(category, message)| {
+        let category = syn::Ident::new(
+            &format!("{category:?}"), 
+            Span::call_site(),
+        );
+        let message = lower_parts(message, rules);
+        quote! { (_, super::plural::Category::#category) => { #message } }
+    });
+    let other = other
+    .into_iter()
+    .map(|(_, message)| lower_parts(message, rules));
+
+    quote! {
+        match super::plural::#select(#rules, *#count) {
+            #(#exact)*
+            #(#categories)*
+            _ => { #(#other)* }
+        }
+    }
+}
+
+fn lower_select(select: &Select, rules: &TokenStream) -> TokenStream {
+    let Select { argument, branches } = select;
+    let argument = parameter(argument);
+
+    let (other, branches): (Vec<_>, Vec<_>) = branches
+    .iter()
+    .partition(|(selector, _)| selector == "other");
+    let branches = branches.into_iter().map(|(selector, message)| {
+        let message = lower_parts(message, rules);
+        quote! { #selector => { #message } }
+    });
+    let other = other
+    .into_iter()
+    .map(|(_, message)| lower_parts(message, rules));
+
+    quote! {
+        match super::select::Select::select(*#argument) {
+            #(#branches)*
+            _ => { #(#other)* }
+        }
+    }
+}
+
+/// Gives the traits each parameter of a key must implement, where `path` 
+/// leads to the module of the key.
+fn bounds(
+    params: &[syn::Ident], 
+    entries: &[Message], 
+    path: &TokenStream,
+) -> Vec<TokenStream> {
+    let counted = entries
+    .iter()
+    .flat_map(Message::plurals)
+    .map(|plural| parameter(&plural.argument))
+    .collect::<Vec<_>>();
+    let selected = entries
+    .iter()
+    .flat_map(Message::selects)
+    .map(|select| parameter(&select.argument))
+    .collect::<Vec<_>>();
+    let displayed = entries
+    .iter()
+    .flat_map(Message::displayed)
+    .collect::<Vec<_>>();
+
+    params
+    .iter()
+    .map(|param| {
+        let mut traits = Vec::new();
+        let counted = counted.contains(param);
+
+        // Counts are displayed anyway
+        if counted { traits.push(quote!(#path plural::Count)); }
+        if selected.contains(param) { traits.push(quote!(#path select::Select)); }
+        let displayed = displayed.iter().any(|name| param == name);
+        if traits.is_empty() || displayed && !counted { 
+            traits.push(quote!(std::fmt::Display)); 
+        }
+
+        quote!(#(#traits)+*)
+    })
+    .collect()
+}
+
+/// Gives the name of the function of a plural rule, e.g. `one_other`.
+fn rule_function(rule: Rule) -> syn::Ident {
+    let mut name = String::new();
+    for c in rule.name().chars() {
+        if c.is_ascii_uppercase() && !name.is_empty() { name.push('_'); }
+        name.push(c.to_ascii_lowercase());
+    }
+    syn::Ident::new(&name, Span::call_site())
+}
+
+/// Gives the parameter name for an argument. Positional ones are referred to
+/// as `argN`.
+fn parameter(argument: &str) -> syn::Ident {
+    let name = if argument.chars().all(char::is_numeric) {
+        format!("arg{argument}")
+    } else {
+        argument.to_owned()
+    };
+    syn::Ident::new(&name, Span::call_site())
+}
+
+/// Gives the parameter names for a key's arguments. Named parameters go 
+/// first, then the positional ones, which the templates refer to as `argN`.
+fn parameters(arguments: &[String]) -> Vec<syn::Ident> {
+    let (positional, named): (Vec<_>, Vec<_>) = arguments
+    .iter()
+    .partition(|a| a.chars().all(char::is_numeric));
+
+    named
+    .into_iter()
+    .chain(positional)
+    .map(|a| parameter(a))
+    .collect()
+}
+
+/// Turns a template without arguments into the text it formats to, i.e. 
+/// removes the escaping of braces.
+fn unescape(template: &str) -> String {
+    template.replace("{{", "{").replace("}}", "}")
 }
 
 fn comment(text: &str) -> Option<TokenStream> {
@@ -1,6 +1,6 @@
 use std::vec;
 
-use crate::{LOCALE_FAILURE_MESSAGE, parser::Parser};
+use crate::parser::Parser;
 
 use super::*;
 
@@ -14,6 +14,200 @@ fn names<const S: usize>(strs: [&str; S]) -> Vec<Name> {
     .unwrap()
 }
 
+/// The conversions of locales, given their variants and codes.
+fn conversions(locales: &[(&str, &str)]) -> TokenStream {
+    let (variants, codes): (Vec<_>, Vec<&&str>) = locales
+    .iter()
+    .map(|(variant, code)| (syn::Ident::new(variant, Span::call_site()), code))
+    .unzip();
+    let count = locales.len();
+    let first = &variants[0];
+
+    quote! {
+        impl Locale {
+            pub const COUNT: usize = #count;
+
+            #[must_use]
+            pub const fn code(self) -> &'static str {
+                match self {
+                    #(Self::#variants => #codes,)*
+                }
+            }
+
+            #[must_use]
+            pub const fn as_index(self) -> usize { self as usize }
+
+            #[must_use]
+            pub const fn from_index(index: usize) -> Option<Self> {
+                if index < Self::COUNT { Some(LOCALES[index]) } else { None }
+            }
+        }
+
+        impl Default for Locale {
+            fn default() -> Self { Self::#first }
+        }
+
+        impl std::fmt::Display for Locale {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.pad(self.code())
+            }
+        }
+
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct UnknownLocale(pub String);
+        impl std::fmt::Display for UnknownLocale {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "unknown locale \"{}\"", self.0)
+            }
+        }
+        impl std::error::Error for UnknownLocale {}
+
+        impl std::str::FromStr for Locale {
+            type Err = UnknownLocale;
+
+            fn from_str(code: &str) -> Result<Self, Self::Err> {
+                Self::lookup(code).ok_or_else(|| UnknownLocale(code.to_owned()))
+            }
+        }
+
+        impl TryFrom<&str> for Locale {
+            type Error = UnknownLocale;
+
+            fn try_from(code: &str) -> Result<Self, Self::Error> {
+                code.parse()
+            }
+        }
+    }
+}
+
+/// The directions of locales, all left-to-right, without native names.
+fn metadata(locales: &[&str]) -> TokenStream {
+    let locales = locales
+    .iter()
+    .map(|l| syn::Ident::new(l, Span::call_site()))
+    .collect::<Vec<_>>();
+
+    quote! {
+        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+        pub enum Direction {
+            Ltr,
+            Rtl,
+        }
+
+        impl Locale {
+            #[must_use]
+            pub const fn direction(self) -> Direction {
+                match self {
+                    #(Self::#locales => Direction::Ltr,)*
+                }
+            }
+        }
+    }
+}
+
+/// The getter, setter, and overrides, which are the same for any locales.
+fn locale_state() -> TokenStream {
+    quote! {
+        pub fn get_locale() -> Locale {
+            THREAD_LOCALE
+            .with(std::cell::Cell::get)
+            .unwrap_or_else(|| 
+                LOCALES[LOCALE.load(std::sync::atomic::Ordering::Relaxed) as usize]
+            )
+        }
+
+        pub fn set_locale(locale: Locale) {
+            LOCALE.store(
+                locale as _, 
+                std::sync::atomic::Ordering::Relaxed,
+            );
+        }
+
+        std::thread_local! {
+            static THREAD_LOCALE: std::cell::Cell<Option<Locale>> = 
+                const { std::cell::Cell::new(None) };
+        }
+
+        pub struct LocaleGuard {
+            previous: Option<Locale>,
+            thread: std::marker::PhantomData<*const ()>,
+        }
+        impl Drop for LocaleGuard {
+            fn drop(&mut self) {
+                THREAD_LOCALE.set(self.previous);
+            }
+        }
+
+        #[must_use = "the override ends when the guard is dropped"]
+        pub fn override_locale(locale: Locale) -> LocaleGuard {
+            LocaleGuard {
+                previous: THREAD_LOCALE.replace(Some(locale)),
+                thread: std::marker::PhantomData,
+            }
+        }
+
+        pub fn with_locale<R>(locale: Locale, f: impl FnOnce() -> R) -> R {
+            let _guard = override_locale(locale);
+            f()
+        }
+    }
+}
+
+/// The negotiation functions, given the arms matching tags to locales.
+fn negotiation(arms: &TokenStream) -> TokenStream {
+    quote! {
+        impl Locale {
+            fn lookup(tag: &str) -> Option<Self> {
+                match tag.to_ascii_lowercase().replace('_', "-").as_str() {
+                    #arms
+                    _ => None,
+                }
+            }
+
+            #[must_use]
+            pub fn negotiate(tags: &[&str]) -> Self {
+                let mut ranges = tags
+                .iter()
+                .flat_map(|tags| tags.split(','))
+                .filter_map(|range| {
+                    let mut parts = range.split(';');
+                    let tag = parts.next()?.trim().split(['.', '@']).next()?;
+                    let quality = parts
+                        .find_map(|part| part.trim().strip_prefix("q="))
+                        .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+
+                    (quality > 0.0 && !tag.is_empty())
+                    .then_some((tag, quality))
+                })
+                .collect::<Vec<_>>();
+
+                ranges.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+                ranges
+                .into_iter()
+                .find_map(|(mut tag, _)| loop {
+                    if let Some(locale) = Self::lookup(tag) { 
+                        return Some(locale); 
+                    }
+                    tag = &tag[..tag.rfind(['-', '_'])?];
+                })
+                .unwrap_or_default()
+            }
+
+            #[must_use]
+            pub fn from_env() -> Self {
+                let vars = ["LC_ALL", "LC_MESSAGES", "LANG"]
+                .into_iter()
+                .filter_map(|var| std::env::var(var).ok())
+                .collect::<Vec<_>>();
+
+                Self::negotiate(
+                    &vars.iter().map(String::as_str).collect::<Vec<_>>()
+                )
+            }
+        }
+    }
+}
 
 fn assert_tokens_eq(expected: &TokenStream, actual: &TokenStream) {
     let expected = expected.to_string();
@@ -38,11 +232,12 @@ fn enum_single_locale() {
     let generator = Generator::new(locales, Vec::new());
     let actual = generator.generate_enum();
     let expected = quote! {
-        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+        #[repr(u8)]
         pub enum Locale { En, }
         pub const LOCALES: [Locale; 1usize] = [ Locale::En, ];
-        pub static LOCALE: std::sync::Mutex<Locale> = 
-                std::sync::Mutex::new(Locale::En);
+        static LOCALE: std::sync::atomic::AtomicU8 = 
+                std::sync::atomic::AtomicU8::new(0);
     }.into_token_stream();
 
     assert_tokens_eq(&expected, &actual);
@@ -55,7 +250,8 @@ fn enum_mutli_locales() {
     let generator = Generator::new(head, Vec::new());
     let actual = generator.generate_enum();
     let expected = quote! {
-        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+        #[repr(u8)]
         pub enum Locale {
             En,
             It,
@@ -66,8 +262,8 @@ fn enum_mutli_locales() {
             Locale::It,
             Locale::Fr,
         ];
-        pub static LOCALE: std::sync::Mutex<Locale> = 
-                std::sync::Mutex::new(Locale::En);
+        static LOCALE: std::sync::atomic::AtomicU8 = 
+                std::sync::atomic::AtomicU8::new(0);
     }.into_token_stream();
 
     assert_tokens_eq(&expected, &actual);
@@ -80,7 +276,8 @@ fn enum_variant_locales() {
     let generator = Generator::new(locales, Vec::new());
     let actual = generator.generate_enum();
     let expected = quote! {
-        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+        #[repr(u8)]
         pub enum Locale {
             EnUs,
             EnUk,
@@ -91,8 +288,8 @@ fn enum_variant_locales() {
             Locale::EnUk,
             Locale::EnIn,
         ];
-        pub static LOCALE: std::sync::Mutex<Locale> = 
-                std::sync::Mutex::new(Locale::EnUs);
+        static LOCALE: std::sync::atomic::AtomicU8 = 
+                std::sync::atomic::AtomicU8::new(0);
     }.into_token_stream();
 
     assert_tokens_eq(&expected, &actual);
@@ -106,17 +303,19 @@ fn single_key_single_locale() {
         arguments: vec![],
         comment: Some(String::from("Common greeting.")),
         entries: vec![
-            String::from("hi"),
-        ]
+            Message::from("hi"),
+        ],
+        inherited: vec![],
+        fallbacks: vec![],
     };
     let generator = Generator::new(locales, vec![key.clone()]);
     let actual = generator.generate_from_key(key);
 
     let expected = quote! {
         #[doc = "Common greeting."]
-        pub fn greet(locale: Locale,) -> String {
+        pub fn greet(locale: Locale) -> &'static str {
             match locale {
-                Locale::En => format!("hi",),
+                Locale::En => "hi",
             }
         }
     };
@@ -132,8 +331,10 @@ fn single_key_single_locale_single_arg() {
         arguments: vec![String::from("name")],
         comment: Some(String::from("Common greeting.")),
         entries: vec![
-            String::from("hi {name}"),
-        ]
+            Message::from("hi {name}"),
+        ],
+        inherited: vec![],
+        fallbacks: vec![],
     };
     let generator = Generator::new(locales, vec![key.clone()]);
     let actual = generator.generate_from_key(key);
@@ -145,7 +346,7 @@ fn single_key_single_locale_single_arg() {
             name: impl std::fmt::Display,
         ) -> String {
             match locale {
-                Locale::En => format!("hi {name}",),
+                Locale::En => format!("hi {name}"),
             }
         }
     };
@@ -165,8 +366,10 @@ fn single_key_single_locale_multi_arg() {
         ],
         comment: Some(String::from("Common greeting.")),
         entries: vec![
-            String::from("hi {0}, {1}, and {2}"),
-        ]
+            Message::from("hi {arg0}, {arg1}, and {arg2}"),
+        ],
+        inherited: vec![],
+        fallbacks: vec![],
     };
     let generator = Generator::new(locales, vec![key.clone()]);
     let actual = generator.generate_from_key(key);
@@ -180,7 +383,7 @@ fn single_key_single_locale_multi_arg() {
             arg2: impl std::fmt::Display,
         ) -> String {
             match locale {
-                Locale::En => format!("hi {0}, {1}, and {2}", arg0, arg1, arg2,),
+                Locale::En => format!("hi {arg0}, {arg1}, and {arg2}"),
             }
         }
     };
@@ -196,20 +399,22 @@ fn single_key_mutli_locale() {
         arguments: vec![],
         comment: None, 
         entries: vec![
-            String::from("oh my god"),
-            String::from("jösses"),
-            String::from("oddio"),
-        ]
+            Message::from("oh my god"),
+            Message::from("jösses"),
+            Message::from("oddio"),
+        ],
+        inherited: vec![],
+        fallbacks: vec![],
     };
     let generator = Generator::new(locales, vec![key.clone()]);
     let actual = generator.generate_from_key(key);
 
     let expected = quote! {
-        pub fn surprise(locale: Locale,) -> String {
+        pub fn surprise(locale: Locale) -> &'static str {
             match locale {
-                Locale::En => format!("oh my god",),
-                Locale::Se => format!("jösses",),
-                Locale::It => format!("oddio",),
+                Locale::En => "oh my god",
+                Locale::Se => "jösses",
+                Locale::It => "oddio",
             }
         }
     };
@@ -225,37 +430,40 @@ fn single_key_single_locale_generate_all() {
         arguments: vec![],
         comment: None, 
         entries: vec![
-            String::from("hi"),
-        ]
+            Message::from("hi"),
+        ],
+        inherited: vec![],
+        fallbacks: vec![],
     };
     let generator = Generator::new(head, vec![key]);
     let actual = generator.generate();
 
+    let conversions = conversions(&[("En", "en")]);
+    let metadata = metadata(&["En"]);
+    let locale_state = locale_state();
+    let negotiation = negotiation(&quote! { "en" => Some(Self::En), });
     let expected = quote! {
-        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+        #[repr(u8)]
         pub enum Locale { En, }
         pub const LOCALES: [Locale; 1usize] = [Locale::En,];
-        pub static LOCALE: std::sync::Mutex<Locale> = 
-                std::sync::Mutex::new(Locale::En);
+        static LOCALE: std::sync::atomic::AtomicU8 = 
+                std::sync::atomic::AtomicU8::new(0);
 
-        pub fn get_locale() -> Locale {
-            *LOCALE
-            .lock()
-            .expect(#LOCALE_FAILURE_MESSAGE)
-        }
+        #conversions
+        #metadata
+        #locale_state
+        #negotiation
 
-        pub fn set_locale(locale: Locale) {
-            *LOCALE
-            .lock()
-            .expect(#LOCALE_FAILURE_MESSAGE)
-                = locale;
-        }
-
-        pub fn greet(locale: Locale,) -> String {
+        pub fn greet(locale: Locale) -> &'static str {
             match locale {
-                Locale::En => format!("hi",),
+                Locale::En => "hi",
             }
         }
+
+        pub mod display {
+            pub use super::greet;
+        }
     };
 
     assert_tokens_eq(&expected, &actual);
@@ -270,52 +478,58 @@ fn multi_key_single_locale_generate_all() {
             arguments: vec![],
             comment: None, 
             entries: vec![
-                String::from("hi"),
-            ]
+                Message::from("hi"),
+            ],
+            inherited: vec![],
+            fallbacks: vec![],
         },
         Key { 
             id: name("other_greet"), 
             arguments: vec![],
             comment: None, 
             entries: vec![
-                String::from("hello"),
-            ]
+                Message::from("hello"),
+            ],
+            inherited: vec![],
+            fallbacks: vec![],
         },
     ];
     let generator = Generator::new(head, keys);
     let actual = generator.generate();
 
+    let conversions = conversions(&[("En", "en")]);
+    let metadata = metadata(&["En"]);
+    let locale_state = locale_state();
+    let negotiation = negotiation(&quote! { "en" => Some(Self::En), });
     let expected = quote! {
-        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+        #[repr(u8)]
         pub enum Locale { En, }
         pub const LOCALES: [Locale; 1usize] = [Locale::En,];
-        pub static LOCALE: std::sync::Mutex<Locale> = 
-                std::sync::Mutex::new(Locale::En);
-
-        pub fn get_locale() -> Locale {
-            *LOCALE
-            .lock()
-            .expect(#LOCALE_FAILURE_MESSAGE)
-        }
+        static LOCALE: std::sync::atomic::AtomicU8 = 
+                std::sync::atomic::AtomicU8::new(0);
 
-        pub fn set_locale(locale: Locale) {
-            *LOCALE
-            .lock()
-            .expect(#LOCALE_FAILURE_MESSAGE)
-                = locale;
-        }
+        #conversions
+        #metadata
+        #locale_state
+        #negotiation
 
-        pub fn greet(locale: Locale,) -> String {
+        pub fn greet(locale: Locale) -> &'static str {
             match locale {
-                Locale::En => format!("hi",),
+                Locale::En => "hi",
             }
         }
 
-        pub fn other_greet(locale: Locale,) -> String {
+        pub fn other_greet(locale: Locale) -> &'static str {
             match locale {
-                Locale::En => format!("hello",),
+                Locale::En => "hello",
             }
         }
+
+        pub mod display {
+            pub use super::greet;
+            pub use super::other_greet;
+        }
     };
 
     assert_tokens_eq(&expected, &actual);
@@ -330,59 +544,65 @@ fn multi_key_multi_locale_generate_all() {
             arguments: vec![],
             comment: None, 
             entries: vec![
-                String::from("hi"),
-                String::from("γεια"),
-            ]
+                Message::from("hi"),
+                Message::from("γεια"),
+            ],
+            inherited: vec![],
+            fallbacks: vec![],
         },
         Key { 
             id: name("other_greet"), 
             arguments: vec![],
             comment: None, 
             entries: vec![
-                String::from("hello"),
-                String::from("καλημέρα"),
-            ]
+                Message::from("hello"),
+                Message::from("καλημέρα"),
+            ],
+            inherited: vec![],
+            fallbacks: vec![],
         },
     ];
     let generator = Generator::new(head, keys);
     let actual = generator.generate();
 
+    let conversions = conversions(&[("En", "en"), ("Gr", "gr")]);
+    let metadata = metadata(&["En", "Gr"]);
+    let locale_state = locale_state();
+    let negotiation = negotiation(&quote! { "en" => Some(Self::En), "gr" => Some(Self::Gr), });
     let expected = quote! {
-        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+        #[repr(u8)]
         pub enum Locale {
             En,
             Gr,
         }
         pub const LOCALES: [Locale; 2usize] = [Locale::En, Locale::Gr,];
-        pub static LOCALE: std::sync::Mutex<Locale> = 
-                std::sync::Mutex::new(Locale::En);
+        static LOCALE: std::sync::atomic::AtomicU8 = 
+                std::sync::atomic::AtomicU8::new(0);
         
-        pub fn get_locale() -> Locale {
-            *LOCALE
-            .lock()
-            .expect(#LOCALE_FAILURE_MESSAGE)
-        }
+        #conversions
+        #metadata
+        #locale_state
+        #negotiation
 
-        pub fn set_locale(locale: Locale) {
-            *LOCALE
-            .lock()
-            .expect(#LOCALE_FAILURE_MESSAGE)
-                = locale;
-        }
-
-        pub fn greet(locale: Locale,) -> String {
+        pub fn greet(locale: Locale) -> &'static str {
             match locale {
-                Locale::En => format!("hi",),
-                Locale::Gr => format!("γεια",),
+                Locale::En => "hi",
+                Locale::Gr => "γεια",
             }
         }
 
-        pub fn other_greet(locale: Locale,) -> String {
+        pub fn other_greet(locale: Locale) -> &'static str {
             match locale {
-                Locale::En => format!("hello",),
-                Locale::Gr => format!("καλημέρα",),
+                Locale::En => "hello",
+                Locale::Gr => "καλημέρα",
             }
         }
+
+        pub mod display {
+            pub use super::greet;
+            pub use super::other_greet;
+        }
     };
 
     assert_tokens_eq(&expected, &actual);
@@ -404,43 +624,468 @@ fn multi_from_text() {
     let generator = Generator::new(parsed.locales, parsed.keys);
     let actual = generator.generate();
 
+    let conversions = conversions(&[("En", "en"), ("Gr", "gr")]);
+    let metadata = metadata(&["En", "Gr"]);
+    let locale_state = locale_state();
+    let negotiation = negotiation(&quote! { "en" => Some(Self::En), "gr" => Some(Self::Gr), });
     let expected = quote! {
-        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+        #[repr(u8)]
         pub enum Locale {
             En,
             Gr,
         }
         pub const LOCALES: [Locale; 2usize] = [Locale::En, Locale::Gr,];
-        pub static LOCALE: std::sync::Mutex<Locale> = 
-                std::sync::Mutex::new(Locale::En);
+        static LOCALE: std::sync::atomic::AtomicU8 = 
+                std::sync::atomic::AtomicU8::new(0);
 
-        pub fn get_locale() -> Locale {
-            *LOCALE
-            .lock()
-            .expect(#LOCALE_FAILURE_MESSAGE)
+        #conversions
+        #metadata
+        #locale_state
+        #negotiation
+
+        pub fn greet(locale: Locale) -> &'static str {
+            match locale {
+                Locale::En => "hi",
+                Locale::Gr => "γεια",
+            }
         }
 
-        pub fn set_locale(locale: Locale) {
-            *LOCALE
-            .lock()
-            .expect(#LOCALE_FAILURE_MESSAGE)
-                = locale;
+        pub fn other_greet(locale: Locale) -> &'static str {
+            match locale {
+                Locale::En => "hello",
+                Locale::Gr => "καλημέρα",
+            }
         }
 
-        pub fn greet(locale: Locale,) -> String {
+        pub mod display {
+            pub use super::greet;
+            pub use super::other_greet;
+        }
+    };
+
+    assert_tokens_eq(&expected, &actual);
+}
+
+#[test]
+fn enum_many_locales() {
+    let locales = (0..300)
+    .map(|i| name(&format!("l{i}")))
+    .collect();
+    
+    let generator = Generator::new(locales, Vec::new());
+    let actual = generator.generate_enum().to_string();
+
+    assert!(actual.contains("# [repr (u16)]"), "{actual}");
+    assert!(actual.contains("AtomicU16"), "{actual}");
+}
+
+#[test]
+fn static_key_unescapes_braces() {
+    let locales = names(["en"]);
+    let key = Key { 
+        id: name("json"), 
+        arguments: vec![],
+        comment: None,
+        entries: vec![
+            Message::from("{{\"a\": 1}}"),
+        ],
+        inherited: vec![],
+        fallbacks: vec![],
+    };
+    let generator = Generator::new(locales, vec![key.clone()]);
+    let actual = generator.generate_from_key(key);
+
+    let expected = quote! {
+        pub fn json(locale: Locale) -> &'static str {
             match locale {
-                Locale::En => format!("hi",),
-                Locale::Gr => format!("γεια",),
+                Locale::En => "{\"a\": 1}",
             }
         }
+    };
+
+    assert_tokens_eq(&expected, &actual);
+}
+
+#[test]
+fn display_with_arguments() {
+    let locales = names(["en", "it"]);
+    let key = Key { 
+        id: name("greet_name"), 
+        arguments: vec![String::from("name"), String::from("0")],
+        comment: None,
+        entries: vec![
+            Message::from("hi {name} {arg0}"),
+            Message::from("ciao {name}"),
+        ],
+        inherited: vec![],
+        fallbacks: vec![],
+    };
+    let generator = Generator::new(locales, vec![key]);
+    let actual = generator.generate_display();
 
-        pub fn other_greet(locale: Locale,) -> String {
+    let expected = quote! {
+        pub mod display {
+            pub struct GreetName<'a, T0: ?Sized, T1: ?Sized,> {
+                pub locale: super::Locale,
+                pub name: &'a T0,
+                pub arg0: &'a T1,
+            }
+
+            impl<T0: std::fmt::Display + ?Sized, T1: std::fmt::Display + ?Sized,> 
+                std::fmt::Display for GreetName<'_, T0, T1,> 
+            {
+                fn fmt(
+                    &self, 
+                    __f: &mut std::fmt::Formatter<'_>,
+                ) -> std::fmt::Result {
+                    let Self { locale, name, arg0, } = self;
+                    match locale {
+                        super::Locale::En => write!(__f, "hi {name} {arg0}"),
+                        super::Locale::It => write!(__f, "ciao {name}"),
+                    }
+                }
+            }
+
+            pub const fn greet_name<'a, T0: ?Sized, T1: ?Sized,>(
+                locale: super::Locale,
+                name: &'a T0,
+                arg0: &'a T1,
+            ) -> GreetName<'a, T0, T1,> {
+                GreetName { locale, name, arg0, }
+            }
+        }
+    };
+
+    assert_tokens_eq(&expected, &actual);
+}
+
+#[test]
+fn display_argument_named_f() {
+    let key = Key { 
+        id: name("scaled"), 
+        arguments: vec![String::from("f")],
+        comment: None,
+        entries: vec![Message::from("{f}x")],
+        inherited: vec![],
+        fallbacks: vec![],
+    };
+    let generator = Generator::new(names(["en"]), vec![key]);
+    let actual = generator.generate_display().to_string();
+
+    // The formatter is not shadowed by the argument
+    let expected = quote! {
+        fn fmt(
+            &self, 
+            __f: &mut std::fmt::Formatter<'_>,
+        ) -> std::fmt::Result {
+            let Self { locale, f, } = self;
             match locale {
-                Locale::En => format!("hello",),
-                Locale::Gr => format!("καλημέρα",),
+                super::Locale::En => write!(__f, "{f}x"),
             }
         }
     };
+    assert!(actual.contains(&expected.to_string()), "{actual}");
+}
+
+#[test]
+fn negotiation_regional_tags() {
+    let generator = Generator::new(names(["en", "en-GB", "pt_br"]), vec![]);
+    let actual = generator.generate_negotiation();
+
+    let expected = negotiation(&quote! {
+        "en" => Some(Self::En), 
+        "en-gb" => Some(Self::EnGb), 
+        "pt-br" => Some(Self::PtBr),
+    });
 
     assert_tokens_eq(&expected, &actual);
 }
+
+#[test]
+fn conversions_keep_declared_codes() {
+    let generator = Generator::new(names(["en", "en-GB", "pt_BR"]), vec![]);
+    let actual = generator.generate_conversions();
+
+    let expected = conversions(&[
+        ("En", "en"), 
+        ("EnGb", "en-GB"), 
+        ("PtBr", "pt_BR"),
+    ]);
+
+    assert_tokens_eq(&expected, &actual);
+}
+
+#[test]
+fn native_names_and_directions() {
+    let generator = Generator::new(names(["en", "ar"]), vec![])
+    .native_names(Some(vec![String::from("English"), String::from("العربية")]))
+    .directions(vec![Direction::Ltr, Direction::Rtl]);
+    let actual = generator.generate_metadata();
+
+    let expected = quote! {
+        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+        pub enum Direction {
+            Ltr,
+            Rtl,
+        }
+
+        impl Locale {
+            #[must_use]
+            pub const fn direction(self) -> Direction {
+                match self {
+                    Self::En => Direction::Ltr,
+                    Self::Ar => Direction::Rtl,
+                }
+            }
+
+            #[must_use]
+            pub const fn native_name(self) -> &'static str {
+                match self {
+                    Self::En => "English",
+                    Self::Ar => "العربية",
+                }
+            }
+        }
+    };
+
+    assert_tokens_eq(&expected, &actual);
+}
+
+#[test]
+fn inherited_entries_share_arms() {
+    let locales = names(["en", "en-gb", "fr", "en-au"]);
+    let key = Key {
+        id: name("colour"),
+        arguments: vec![String::from("name")],
+        comment: None,
+        entries: vec![
+            Message::from("{name}'s color"),
+            Message::from("{name}'s colour"),
+            Message::from("la couleur de {name}"),
+            Message::from("{name}'s colour"),
+        ],
+        inherited: vec![(3, 1)],
+        fallbacks: vec![],
+    };
+    let generator = Generator::new(locales, vec![key.clone()]);
+    let actual = generator.generate_from_key(key);
+
+    let expected = quote! {
+        pub fn colour(
+            locale: Locale,
+            name: impl std::fmt::Display,
+        ) -> String {
+            match locale {
+                Locale::En => format!("{name}'s color"),
+                Locale::EnGb | Locale::EnAu => format!("{name}'s colour"),
+                Locale::Fr => format!("la couleur de {name}"),
+            }
+        }
+    };
+
+    assert_tokens_eq(&expected, &actual);
+}
+
+#[test]
+fn plurals_are_lowered() {
+    let source = "
+        !locales en fr-CA
+        files:
+            en \"{n, plural, =0 {No files} one {# file} other {# files}}!\"
+            fr-CA \"{n, plural, one {# fichier} other {# fichiers}}!\"
+    ";
+    let parsed = Parser::from_text(source).parse().unwrap();
+    let key = parsed.keys[0].clone();
+    let generator = Generator::new(parsed.locales, parsed.keys);
+
+    let expected = quote! {
+        pub fn files(
+            locale: Locale,
+            n: impl plural::Count,
+        ) -> String {
+            display::files(locale, &n).to_string()
+        }
+    };
+    assert_tokens_eq(&expected, &generator.generate_from_key(key));
+
+    let expected = quote! {
+        pub mod display {
+            pub struct Files<'a, T0: ?Sized,> {
+                pub locale: super::Locale,
+                pub n: &'a T0,
+            }
+
+            impl<T0: super::plural::Count + ?Sized,> 
+                std::fmt::Display for Files<'_, T0,> 
+            {
+                fn fmt(
+                    &self, 
+                    __f: &mut std::fmt::Formatter<'_>,
+                ) -> std::fmt::Result {
+                    let Self { locale, n, } = self;
+                    match locale {
+                        super::Locale::En => {
+                            match super::plural::select(*locale, *n) {
+                                (0u64, _) => { write!(__f, "No files")?; }
+                                (_, super::plural::Category::One) => {
+                                    write!(__f, "{n} file")?;
+                                }
+                                _ => { write!(__f, "{n} files")?; }
+                            }
+                            write!(__f, "!")?;
+                            Ok(())
+                        },
+                        super::Locale::FrCa => {
+                            match super::plural::select(*locale, *n) {
+                                (_, super::plural::Category::One) => {
+                                    write!(__f, "{n} fichier")?;
+                                }
+                                _ => { write!(__f, "{n} fichiers")?; }
+                            }
+                            write!(__f, "!")?;
+                            Ok(())
+                        },
+                    }
+                }
+            }
+
+            pub const fn files<'a, T0: ?Sized,>(
+                locale: super::Locale,
+                n: &'a T0,
+            ) -> Files<'a, T0,> {
+                Files { locale, n, }
+            }
+        }
+    };
+    assert_tokens_eq(&expected, &generator.generate_display());
+
+    let actual = generator.generate_plurals().unwrap().to_string();
+    assert!(actual.contains(&quote! {
+        pub fn category(locale: super::Locale, n: u64) -> Category {
+            match locale {
+                super::Locale::En => one_other(n),
+                super::Locale::FrCa => french(n),
+            }
+        }
+    }.to_string()), "{actual}");
+    assert!(actual.contains(&quote!(fn french(n: u64) -> Category).to_string()));
+}
+
+#[test]
+fn ordinals_are_lowered() {
+    let source = "
+        !locales en fr
+        place:
+            en \"{n, selectordinal, one {#st} two {#nd} few {#rd} other {#th}}\"
+            fr \"{n, selectordinal, one {#er} other {#e}}\"
+    ";
+    let parsed = Parser::from_text(source).parse().unwrap();
+    let generator = Generator::new(parsed.locales, parsed.keys);
+
+    let actual = generator.generate_display().to_string();
+    let expected = quote! {
+        super::Locale::Fr => {
+            match super::plural::select_ordinal(*locale, *n) {
+                (_, super::plural::Category::One) => { write!(__f, "{n}er")?; }
+                _ => { write!(__f, "{n}e")?; }
+            }
+            Ok(())
+        },
+    };
+    assert!(actual.contains(&expected.to_string()), "{actual}");
+
+    // Only ordinals are used, so there are no cardinal rules
+    let actual = generator.generate_plurals().unwrap().to_string();
+    assert!(actual.contains(&quote! {
+        pub fn ordinal_category(locale: super::Locale, n: u64) -> Category {
+            match locale {
+                super::Locale::En => english_ordinal(n),
+                super::Locale::Fr => first_ordinal(n),
+            }
+        }
+    }.to_string()), "{actual}");
+    assert!(!actual.contains(&quote!(pub fn category).to_string()));
+}
+
+#[test]
+fn fallback_plurals_are_lowered() {
+    let source = "
+        !locales en pl pl-pl:pl
+        !fallback pl en
+        files:
+            en \"{n, plural, one {# file} other {# files}}\"
+    ";
+    let parsed = Parser::from_text(source).parse().unwrap();
+    let generator = Generator::new(parsed.locales, parsed.keys);
+
+    // The entry picks its branch by the rules it was written for
+    let actual = generator.generate_display().to_string();
+    let expected = quote! {
+        super::Locale::Pl | super::Locale::PlPl => {
+            match super::plural::select(super::Locale::En, *n) {
+                (_, super::plural::Category::One) => {
+                    write!(__f, "{n} file")?;
+                }
+                _ => { write!(__f, "{n} files")?; }
+            }
+            Ok(())
+        },
+    };
+    assert!(actual.contains(&expected.to_string()), "{actual}");
+}
+
+#[test]
+fn plurals_only_when_used() {
+    let generator = Generator::new(names(["en"]), vec![]);
+    assert!(generator.generate_plurals().is_none());
+}
+
+#[test]
+fn selects_are_lowered() {
+    let source = "
+        !locales en
+        role:
+            en \"{role, select, admin {Admin} other {{role}}}!\"
+    ";
+    let parsed = Parser::from_text(source).parse().unwrap();
+    let key = parsed.keys[0].clone();
+    let generator = Generator::new(parsed.locales, parsed.keys);
+
+    let expected = quote! {
+        pub fn role(
+            locale: Locale,
+            role: impl select::Select + std::fmt::Display,
+        ) -> String {
+            display::role(locale, &role).to_string()
+        }
+    };
+    assert_tokens_eq(&expected, &generator.generate_from_key(key));
+
+    let actual = generator.generate_display().to_string();
+    let expected = quote! {
+        impl<T0: super::select::Select + std::fmt::Display + ?Sized,> 
+            std::fmt::Display for Role<'_, T0,> 
+        {
+            fn fmt(
+                &self, 
+                __f: &mut std::fmt::Formatter<'_>,
+            ) -> std::fmt::Result {
+                let Self { locale, role, } = self;
+                match locale {
+                    super::Locale::En => {
+                        match super::select::Select::select(*role) {
+                            "admin" => { write!(__f, "Admin")?; }
+                            _ => { write!(__f, "{role}")?; }
+                        }
+                        write!(__f, "!")?;
+                        Ok(())
+                    },
+                }
+            }
+        }
+    };
+    assert!(actual.contains(&expected.to_string()), "{actual}");
+    assert!(generator.generate_selects().is_some());
+    assert!(generator.generate_plurals().is_none());
+}
@@ -1,11 +1,10 @@
 #![doc = include_str!("../readme.md")]
 
-pub const LOCALE_FAILURE_MESSAGE: &str = "could not acquire current locale";
-
 pub mod error;
 pub mod name;
 pub mod reader;
 pub mod parser;
+pub mod plural;
 pub mod generator;
 
 fn shorten(line: impl AsRef<str>) -> String {
@@ -4,8 +4,12 @@ use crate::reader::ReadError;
 mod tests;
 
 /// Just a string where every char is guaranteed to be valid.
-#[derive(Debug, PartialEq, Eq, Clone)]
-pub struct Name(String);
+#[derive(Debug, Clone)]
+pub struct Name {
+    name: String,
+    /// The name as it was written, before being made valid.
+    written: String,
+}
 impl Name {
     /// Creates a new name. Note that an empty name is not valid, and so the 
     /// first (or only) char must be given.
@@ -17,25 +21,36 @@ impl Name {
     /// Allocates for 5 characters, as that is enough for most regular locales,
     /// e.g. "en-uk".
     pub fn new(first: char) -> Result<Self, ReadError> { 
-        let mut inner = String::with_capacity(5);
-        inner.push(Self::validate_first_char(first)?);
+        let mut name = String::with_capacity(5);
+        name.push(Self::validate_first_char(first)?);
 
-        Ok(Self(inner))
+        Ok(Self { 
+            name, 
+            written: String::from(first),
+        })
     }
 
     #[must_use]
-    pub fn to_str(&self) -> &str { &self.0 }
+    pub fn to_str(&self) -> &str { &self.name }
+
+    #[must_use]
+    /// Gives the name as it was written, e.g. `en-GB` rather than `en_gb`.
+    pub fn as_written(&self) -> &str { &self.written }
 
     /// Adds a char.
     /// 
     /// # Errors
     /// If the char is not valid.
     pub fn add(&mut self, char: char) -> Result<(), ReadError> {
-        if self.0.is_empty() {
-            Self::validate_first_char(char).map(|c| self.0.push(c))
+        let c = if self.name.is_empty() {
+            Self::validate_first_char(char)?
         } else {
-            Self::validate_char(char).map(|c| self.0.push(c))
-        }
+            Self::validate_char(char)?
+        };
+
+        self.name.push(c);
+        self.written.push(char);
+        Ok(())
     }
 
     /// Returns the valid version of any supplied char.
@@ -67,7 +82,7 @@ impl Name {
     #[must_use]
     /// Gives a name suitable for a type or variant
     pub fn type_name(&self) -> String {
-        self.0
+        self.name
         .split('_')
         .filter_map(|p| {
             // Capitalise first letter
@@ -86,14 +101,19 @@ impl Name {
 }
 impl std::fmt::Display for Name {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Name \"{}\"", self.0)
+        write!(f, "Name \"{}\"", self.name)
     }
 }
+// Names are the same however they were written
+impl PartialEq for Name {
+    fn eq(&self, other: &Self) -> bool { self.name == other.name }
+}
+impl Eq for Name {}
 impl From<Name> for String {
-    fn from(value: Name) -> Self { value.0 }
+    fn from(value: Name) -> Self { value.name }
 }
 impl AsRef<str> for Name {
-    fn as_ref(&self) -> &str { &self.0 }
+    fn as_ref(&self) -> &str { &self.name }
 }
 impl TryFrom<&str> for Name {
     type Error = ReadError;
@@ -107,7 +127,10 @@ impl TryFrom<&str> for Name {
 
         // would allocate the correct number, but probably takes longer
         // let len = value.chars().count();
-        let mut name = Self(String::with_capacity(len));
+        let mut name = Self {
+            name: String::with_capacity(len),
+            written: String::with_capacity(len),
+        };
         for c in value.chars() { name.add(c)?; }
         Ok(name)
     }
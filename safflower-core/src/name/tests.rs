@@ -28,3 +28,12 @@ fn ok_names() {
     }
 }
 
+
+#[test]
+fn written_names() {
+    let name = Name::try_from("en-GB").unwrap();
+    
+    assert_eq!(name.to_str(), "en_gb");
+    assert_eq!(name.as_written(), "en-GB");
+    assert_eq!(name, Name::try_from("EN_gb").unwrap());
+}
@@ -1,28 +1,48 @@
 use std::path::{Path, PathBuf};
 
 use crate::{
-    error::Error, name::Name, reader::{CharReader, ReadError, Token}, shorten
+    error::{Error, Location}, 
+    name::Name, 
+    plural,
+    reader::{CharReader, Position, Read, Token}, 
+    shorten,
 };
 
 mod error;
 mod config;
+mod message;
 pub use error::ParseError;
-use config::Configuration;
+pub use config::{Direction, Syntax};
+pub use message::{Message, Part, Plural, Select};
+use config::{Configuration, Level};
+use message::MessageParser;
 
 #[cfg(test)]
 mod tests;
 
 /// Parses iterators of safflower tokens.
 pub struct Parser {
-    tokens: Box<dyn Iterator<Item = Result<Token, ReadError>>>,
-    buffer: Option<Token>,
+    tokens: Box<dyn Iterator<Item = Read>>,
+    /// A token, or reading error, read ahead and put back.
+    buffer: Option<Read>,
 
     read_paths: Vec<PathBuf>,
+    /// The text of the file currently being read, for error reporting.
+    source: String,
+    /// The position of the last token read.
+    position: Position,
 
     config: Configuration,
+    /// Where each locale was declared.
+    locale_locations: Vec<Location>,
     keys: Vec<TempKey>,
 
     comment: Option<String>,
+
+    errors: Vec<Error>,
+    reports: Reports,
+    /// Keys that could not be parsed, and so should not be validated.
+    failed: Vec<Name>,
 }
 impl Parser {
     /// Creates a parser to read from a file path.
@@ -41,11 +61,18 @@ impl Parser {
             buffer: None,
 
             read_paths,
+            source,
+            position: Position::default(),
 
             config: Configuration::new(path.as_ref().into()),
+            locale_locations: vec![],
             keys: vec![],
             
             comment: None,
+
+            errors: vec![],
+            reports: Reports::default(),
+            failed: vec![],
         })
     }
 
@@ -59,20 +86,28 @@ impl Parser {
             buffer: None,
 
             read_paths: vec![],
+            source: text.to_string(),
+            position: Position::default(),
 
             config: Configuration::new(PathBuf::from("string")),
+            locale_locations: vec![],
             keys: vec![],
             
             comment: None,
+
+            errors: vec![],
+            reports: Reports::default(),
+            failed: vec![],
         }
     }
 
+    #[must_use]
     #[cfg(test)]
     pub fn from_vec(source: Vec<Token>) -> Self {
         let tokens = Box::new(
             source
             .into_iter()
-            .map(Ok)
+            .map(|t| Ok((Position::default(), t)))
             .collect::<Vec<_>>()
             .into_iter()
         );
@@ -82,69 +117,162 @@ impl Parser {
             buffer: None,
 
             read_paths: vec![],
+            source: String::new(),
+            position: Position::default(),
 
             config: Configuration::new(PathBuf::from("vec")),
+            locale_locations: vec![],
             keys: vec![],
             
             comment: None,
+
+            errors: vec![],
+            reports: Reports::default(),
+            failed: vec![],
         }
     }  
 
     fn refill_tokens(&mut self) -> Result<bool, Error> {
         let Some(path) = self.config.pop_path() else { return Ok(false); };
 
-        if self.read_paths.contains(&path) {
-            return Err(self.contextualise(
-                ParseError::ConfigDuplicateFile(path)
-            ));
-        }
-
         let source = std::fs::read_to_string(&path)
-        .map_err(|e| Error::Io(
-            self.config.current_path.clone(),
-            e,
-        ))?;
+        .map_err(|e| Error::Io(path.clone(), e))?;
         
         self.read_paths.push(path);
 
         self.tokens = Box::new(CharReader::new(&source));
+        self.source = source;
+        self.position = Position::default();
 
         Ok(true)
     }
 
+    /// Takes the buffered token if there is one, otherwise reads the next.
+    fn next_token(&mut self) -> Result<Option<Token>, Error> {
+        let next = self.buffer.take().or_else(|| self.tokens.next());
+
+        match next {
+            Some(Ok((position, token))) => {
+                self.position = position;
+                Ok(Some(token))
+            },
+            Some(Err((position, e))) => Err(Error::Read(
+                self.locate(position).into(), 
+                e,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether the next token is a reading error, which is kept for later.
+    fn next_is_error(&mut self) -> bool {
+        if self.buffer.is_none() { self.buffer = self.tokens.next(); }
+        matches!(self.buffer, Some(Err(_)))
+    }
+
     /// Parses all tokens and returns the parsed data.
     /// 
     /// # Errors 
-    /// If something is unparsable.
-    pub fn parse(mut self) -> Result<ParsedData, Error> {
+    /// If anything is unparsable. Parsing picks up again at the next key or
+    /// config line, so that every error in the files is returned.
+    pub fn parse(mut self) -> Result<ParsedData, Vec<Error>> {
         loop {
-            let token = match self.buffer.take() {
-                Some(t) => Some(t),
-                None => self.tokens.next().transpose()?,
+            let result = match self.next_token() {
+                Ok(Some(t)) => self.parse_token(t),
+                Ok(None) => match self.refill_tokens() {
+                    Ok(true) => continue,
+                    Ok(false) => break,
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
             };
-            
-            match token {
-                Some(t) => self.parse_token(t)?,
-                None => if !self.refill_tokens()? { break },
+
+            if let Err(e) = result {
+                self.errors.push(e);
+                self.recover();
             }
         }
 
-        let keys = std::mem::take(&mut self.keys);
+        let mut keys = Vec::with_capacity(self.keys.len());
+        let mut fallbacks = vec![0; self.config.locale_count()];
+        let mut fallback_location = None;
+        for mut key in std::mem::take(&mut self.keys) {
+            if self.failed.contains(&key.id) { continue; }
+
+            key.fill_missing(&self.config);
+            if !key.fallbacks.is_empty() { 
+                fallback_location.get_or_insert_with(|| key.location.clone());
+            }
+            for &(locale, _) in &key.fallbacks { fallbacks[locale] += 1; }
+
+            match key.validate(&self.config, &mut self.reports) {
+                Ok(key) => keys.push(key),
+
+                // Every other key would fail the same way
+                Err(e @ Error::Parse(_, ParseError::NoLocales)) => {
+                    self.errors.push(e);
+                    break;
+                },
 
-        let keys = keys
-        .into_iter()
-        .map(|key| key.validate(&self.config.locales))
-        .collect::<Result<_, ParseError>>()
-        .map_err(|e| self.contextualise(e))?;
+                Err(e) => self.errors.push(e),
+            }
+        }
+
+        if let Some(location) = fallback_location {
+            let error = Error::Parse(
+                location.into(), 
+                self.fallback_summary(&fallbacks),
+            );
+            if let Err(e) = self.reports.report(self.config.fallback, error) {
+                self.errors.push(e);
+            }
+        }
+
+        let native_names = self.native_names();
+        let directions = self.config.locales
+        .iter()
+        .map(|locale| self.config.directions
+            .iter()
+            .find_map(|(l, d)| (l == locale).then_some(*d))
+            .unwrap_or_default()
+        )
+        .collect();
+
+        if !self.errors.is_empty() { return Err(self.errors); }
 
         let locales = self.config.locales;
+        let paths = self.read_paths;
+        let Reports { warnings, release_errors } = self.reports;
 
         Ok(ParsedData {
             locales,
+            native_names,
+            directions,
             keys,
+            paths,
+            warnings,
+            release_errors,
         })
     }
 
+    /// Skips ahead to the next key or config line, to pick up parsing after
+    /// an error.
+    fn recover(&mut self) {
+        self.comment = None;
+
+        loop {
+            match self.next_token() {
+                Ok(Some(t @ (Token::Key(_) | Token::Config(_)))) => {
+                    self.buffer = Some(Ok((self.position, t)));
+                    return;
+                },
+                Ok(Some(_)) => {},
+                Ok(None) => return,
+                Err(e) => self.errors.push(e),
+            }
+        }
+    }
+
     fn parse_token(&mut self, token: Token) -> Result<(), Error> {
         // The are only a few valid token sequences:
         // 1) !config values
@@ -152,7 +280,16 @@ impl Parser {
         // 3) #comment (2)
         match token {
             Token::Config(c) => {
-                self.config.parse_config(&c)?;
+                let result = self.config.parse_config(&c);
+
+                // Even a failed line may have declared some locales
+                let declared = self.config.locale_count();
+                while self.locale_locations.len() < declared {
+                    self.locale_locations.push(self.locate(self.position));
+                }
+
+                result.map_err(|e| self.contextualise(e))?;
+
                 // In case a comment was read before, it should be removed
                 self.comment = None;
             },
@@ -173,13 +310,35 @@ impl Parser {
     }
 
     fn parse_key(&mut self, id: Name) -> Result<(), Error> {
+        let location = self.locate(self.position);
+        let comment = self.comment.take();
+
+        let entries = match self.parse_entries() {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.failed.push(id);
+                return Err(e);
+            },
+        };
+
+        let key = TempKey {
+            id,
+            location,
+            comment,
+            entries,
+            inherited: vec![],
+            fallbacks: vec![],
+        };
+
+        self.add_key(key)
+    }
+
+    fn parse_entries(&mut self) -> Result<Vec<Option<Entry>>, Error> {
         // We have a key, so we must now get all the locale-value pairs
         let mut entries = vec![None; self.config.locale_count()];
         let mut did_something = false;
 
-        let comment = self.comment.take();
-        loop {
-            let Some(locale) = self.get_locale()? else { break; };
+        while let Some(locale) = self.get_locale()? {
             let index = self.config
             .find_locale(&locale)
             .ok_or_else(|| self.contextualise(
@@ -188,8 +347,11 @@ impl Parser {
 
             let Some(value) = self.get_value()? else { break; };
             let comment = self.comment.take();
+            let location = self.locate(self.position);
 
-            entries[index] = Some(Entry { value, comment });
+            let syntax = self.config.syntax;
+
+            entries[index] = Some(Entry { value, comment, location, syntax });
             did_something = true;
         }
 
@@ -197,25 +359,24 @@ impl Parser {
             return Err(self.contextualise(ParseError::ExpectedLocale));
         }
 
-        let key = TempKey {
-            id,
-            comment,
-            entries,
-        };
-
-        self.add_key(key).map_err(|e| self.contextualise(e))
+        Ok(entries)
     }
 
     fn get_locale(&mut self) -> Result<Option<Name>, Error> {
-        for t in self.tokens.by_ref() {
-            match t? {
+        loop {
+            // A broken line may just as well start the next key, so the key
+            // ends here, and the error is left to be reported on its own
+            if self.next_is_error() { return Ok(None); }
+            let Some(t) = self.next_token()? else { return Ok(None); };
+
+            match t {
                 Token::Comment(c) => self.comment = Some(c),
                 Token::Locale(id) => return Ok(Some(id)),
 
                 // We expect key - loc - val - loc - val ...
-                // until there is a key again
-                Token::Key(id) => { 
-                    self.buffer = Some(Token::Key(id)); 
+                // until there is a key or config line again
+                t @ (Token::Key(_) | Token::Config(_)) => { 
+                    self.buffer = Some(Ok((self.position, t))); 
                     return Ok(None);
                 }
 
@@ -224,12 +385,11 @@ impl Parser {
                 )),
             }
         }
-        Ok(None)
     }
 
     fn get_value(&mut self) -> Result<Option<String>, Error> {
-        for t in self.tokens.by_ref() {
-            match t? {
+        while let Some(t) = self.next_token()? {
+            match t {
                 Token::Comment(c) => self.comment = Some(c),
                 Token::Value(value) => return Ok(Some(value)),
 
@@ -241,10 +401,10 @@ impl Parser {
         Err(self.contextualise(ParseError::ExpectedValue))
     }
 
-    fn add_key(&mut self, key: TempKey) -> Result<(), ParseError> {
+    fn add_key(&mut self, key: TempKey) -> Result<(), Error> {
         // Check if an old key matches the new one
         if let Some(old_key) = self.keys.iter_mut().find(|k| k.id == key.id) {
-            let TempKey { id, comment, entries } = key;
+            let TempKey { id, comment, entries, .. } = key;
 
             if old_key.entries.len() < entries.len() {
                 let size_difference = entries.len() - old_key.entries.len();
@@ -253,16 +413,19 @@ impl Parser {
 
             // If no entries overlap, it's ok, otherwise it's an error
             for (i, e) in entries.into_iter().enumerate() {
-                if e.is_none() { continue; }
+                let Some(e) = e else { continue; };
 
                 if old_key.entries[i].is_some() {
-                    return Err(ParseError::DuplicateEntry(
-                        id.into(),
-                        self.config.locales[i].to_str().into(),
+                    return Err(Error::Parse(
+                        e.location.into(),
+                        ParseError::DuplicateEntry(
+                            self.config.locales[i].to_str().into(),
+                            id.into(),
+                        ),
                     ));
                 }
 
-                old_key.entries[i] = e;
+                old_key.entries[i] = Some(e);
             }
 
             // Join the comments as well
@@ -280,132 +443,310 @@ impl Parser {
         Ok(())
     }
 
-    fn contextualise(&self, err: ParseError) -> Error {
-        Error::Parse(
+    /// Sums up how many entries of each locale were taken from fallbacks.
+    fn fallback_summary(&self, counts: &[usize]) -> ParseError {
+        let summary = counts
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count > 0)
+        .map(|(i, count)| 
+            format!("{count} for \"{}\"", self.config.locales[i].as_written())
+        )
+        .collect::<Vec<_>>()
+        .join(", ");
+
+        ParseError::FallbackUsed(counts.iter().sum(), summary)
+    }
+
+    /// Gives the native name of every locale, if any have been named. A 
+    /// locale missing its name is an error.
+    fn native_names(&mut self) -> Option<Vec<String>> {
+        if self.config.native_names.is_empty() { return None; }
+
+        let mut names = Vec::with_capacity(self.config.locale_count());
+        for (i, locale) in self.config.locales.iter().enumerate() {
+            let name = self.config.native_names
+            .iter()
+            .find_map(|(l, name)| (l == locale).then(|| name.clone()));
+
+            match name {
+                Some(name) => names.push(name),
+                None => self.errors.push(Error::Parse(
+                    self.locale_locations[i].clone().into(),
+                    ParseError::MissingLocaleName(locale.to_str().into()),
+                )),
+            }
+        }
+
+        Some(names)
+    }
+
+    /// Finds where in the current file a position is.
+    fn locate(&self, position: Position) -> Location {
+        Location::new(
             self.config.current_path.clone(), 
-            err,    
+            &self.source, 
+            position,
         )
     }
+
+    /// Attaches the location of the last read token to an error.
+    fn contextualise(&self, err: ParseError) -> Error {
+        Error::Parse(self.locate(self.position).into(), err)
+    }
 }
 
 #[derive(Debug)]
 /// The collected data once the parsing is finished.
 pub struct ParsedData {
     pub locales: Vec<Name>,
+    /// The name of each locale in its own language, if they were given.
+    pub native_names: Option<Vec<String>>,
+    /// The direction of each locale.
+    pub directions: Vec<Direction>,
     pub keys: Vec<Key>,
+    /// Every file that was read, in the order they were read.
+    pub paths: Vec<PathBuf>,
+    /// Problems that are allowed, but should be reported.
+    pub warnings: Vec<Error>,
+    /// Problems that are allowed, but only in debug builds.
+    pub release_errors: Vec<Error>,
+}
+
+/// Problems that are allowed, depending on their lint's [`Level`].
+#[derive(Default)]
+struct Reports {
+    warnings: Vec<Error>,
+    release_errors: Vec<Error>,
+}
+impl Reports {
+    /// Reports a problem, or gives it back if it is not allowed.
+    fn report(&mut self, level: Level, error: Error) -> Result<(), Error> {
+        match level {
+            Level::Warn => self.warnings.push(error),
+            Level::DenyRelease => self.release_errors.push(error),
+            Level::Deny => return Err(error),
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
 struct TempKey {
     id: Name,
+    /// Where the key was first found.
+    location: Location,
     comment: Option<String>,
     entries: Vec<Option<Entry>>,
+    /// Locales that inherited their entry, and where the entry is from.
+    inherited: Vec<(usize, usize)>,
+    /// Locales that took their entry from a fallback, and where it is from.
+    fallbacks: Vec<(usize, usize)>,
 }
 impl TempKey {
-    fn validate(self, locales: &[Name]) -> Result<Key, ParseError> {
-        if locales.is_empty() { return Err(ParseError::NoLocales); }
-        
-        let Self { id, comment, entries } = self;
+    /// Fills in missing entries, of regional locales from the locales they 
+    /// inherit from, and otherwise from the fallbacks of their locales.
+    fn fill_missing(&mut self, config: &Configuration) {
+        self.entries.resize(config.locale_count(), None);
+
+        // Only entries actually written are fallen back to
+        let written = self.entries.clone();
+
+        // Parents are declared before their children, so the entry of a 
+        // parent is always filled, however it is, before its children need it
+        for i in 0..self.entries.len() {
+            if self.entries[i].is_some() { continue; }
+
+            let inherited = config
+            .parent(i)
+            .and_then(|parent| Some((parent, self.entries[parent].clone()?)));
+            if let Some((parent, entry)) = inherited {
+                let source = self.inherited
+                .iter()
+                .find_map(|&(l, from)| (l == parent).then_some(from))
+                .unwrap_or(parent);
+
+                self.entries[i] = Some(Entry { comment: None, ..entry });
+                self.inherited.push((i, source));
+                continue;
+            }
 
-        let (entries, comments) = get_entries(entries, &id, locales)?;
-        let comment = get_comment(comments, comment, locales);
-        let arguments = get_arguments(&entries, &id, locales)?;
+            let fallback = config
+            .fallback_chain(i)
+            .into_iter()
+            .find_map(|from| Some((from, written[from].clone()?)));
+
+            if let Some((from, fallback)) = fallback {
+                // The notes on the entry were meant for its own locale
+                self.entries[i] = Some(Entry { comment: None, ..fallback });
+                self.fallbacks.push((i, from));
+            }
+        }
+    }
+
+    fn validate(
+        self, 
+        config: &Configuration,
+        reports: &mut Reports,
+    ) -> Result<Key, Error> {
+        let Self { 
+            id, 
+            location, 
+            comment, 
+            entries, 
+            inherited, 
+            fallbacks,
+        } = self;
+        let locales = config.locales.as_slice();
+
+        if locales.is_empty() { 
+            return Err(Error::Parse(location.into(), ParseError::NoLocales)); 
+        }
+
+        let entries = get_entries(entries, &id, locales)
+        .map_err(|e| Error::Parse(location.into(), e))?;
+
+        // An entry taken from a fallback, even through a parent, was written 
+        // for the plural rules of the locale it is from
+        let plural_locale = |i: usize| {
+            let root = inherited
+            .iter()
+            .find_map(|&(l, from)| (l == i).then_some(from))
+            .unwrap_or(i);
+            fallbacks
+            .iter()
+            .find_map(|&(l, from)| (l == root).then_some(from))
+            .unwrap_or(i)
+        };
+
+        let messages = entries
+        .iter()
+        .enumerate()
+        .map(|(i, e)| MessageParser::parse(&e.value, e.syntax)
+            .and_then(|(arguments, message)| {
+                check_plurals(&message, &locales[plural_locale(i)])?;
+                Ok((arguments, message))
+            })
+            .map_err(|err| Error::Parse(e.location.clone().into(), err))
+        )
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let (arguments, unused) = get_arguments(&messages, &id, locales);
+        for (index, error) in unused {
+            let error = Error::Parse(
+                entries[index].location.clone().into(), 
+                error,
+            );
+
+            reports.report(config.unused_arguments, error)?;
+        }
+
+        let comments = entries.into_iter().map(|e| e.comment).collect();
+        let comment = get_comment(comments, comment, &inherited, locales);
+        let entries = messages.into_iter().map(|(_, m)| m).collect();
 
         Ok(Key {
             id,
             arguments,
             comment,
             entries,
+            inherited,
+            fallbacks,
         })
     }
 }
 
+/// Collects the arguments of every entry, in order of first appearance, and 
+/// notes which entries leave some of them out.
 fn get_arguments(
-    entries: &[String], 
+    messages: &[(Vec<String>, Message)], 
     id: &Name,
     locales: &[Name],
-) -> Result<Vec<String>, ParseError> {
-    let arguments = extract_arguments(&entries[0])?;
-        
-    let mismatch = entries
+) -> (Vec<String>, Vec<(usize, ParseError)>) {
+    let mut arguments = Vec::<String>::new();
+    for argument in messages.iter().flat_map(|(a, _)| a) {
+        if !arguments.contains(argument) { 
+            arguments.push(argument.clone());
+        }
+    }
+
+    let unused = messages
     .iter()
     .enumerate()
-    .skip(1)
-    .map(|(i, e)| (i, extract_arguments(e)))
-    .find(|(_, a)| !a.as_ref().is_ok_and(|a| a == &arguments));
-
-    if let Some((index, result)) = mismatch {
-        let args = result?;
-        return Err(ParseError::ArgumentMismatch(
+    .filter_map(|(i, (args, _))| {
+        let missing = arguments
+        .iter()
+        .filter(|a| !args.contains(a))
+        .cloned()
+        .collect::<Vec<_>>();
+
+        (!missing.is_empty()).then(|| (i, ParseError::ArgumentUnused(
             id.to_str().to_string(), 
-            locales[index].to_str().to_string(),
-            args,
-            arguments,
-        ));
-    }
+            locales[i].as_written().to_string(),
+            missing,
+        )))
+    })
+    .collect();
 
-    Ok(arguments)
+    (arguments, unused)
 }
 
-fn extract_arguments(key: &str) -> Result<Vec<String>, ParseError> {
-    let mut arguments = Vec::new();
-    let mut argument = String::new();
-    let mut opened = false;
-    let mut unnamed_indexer = 0;
-    let mut formatting = false;
-
-    for c in key.chars() {
-        match c {
-            '{' if opened => return Err(ParseError::NestedBrace),
-            '{' => { opened = true; },
-
-            '}' if !opened => return Err(ParseError::ExtraClosingBrace),
-            '}' => {
-                if argument.is_empty() {
-                    argument = format!("{unnamed_indexer}");
-                    unnamed_indexer += 1;
-                }
-                else if !argument.starts_with(
-                    |c: char| c.is_ascii_alphabetic()
-                ) && !argument.chars().all(char::is_numeric)  {
-                    return Err(ParseError::ArgBadStart(
-                        key.to_string(), 
-                        shorten(&argument), 
-                        c,
-                    ))
-                }
-
-                if !arguments.contains(&argument) {                        
-                    arguments.push(argument);
-                }
+#[cfg(test)]
+fn extract_arguments(value: &str) -> Result<Vec<String>, ParseError> {
+    MessageParser::parse(value, Syntax::Format).map(|(arguments, _)| arguments)
+}
 
-                argument = String::new();
-                opened = false;
-                formatting = false;
-            }
+/// Checks that every plural of a message gives the categories its locale 
+/// needs, and no others.
+fn check_plurals(message: &Message, locale: &Name) -> Result<(), ParseError> {
+    let plurals = message.plurals();
+    if plurals.is_empty() { return Ok(()); }
+
+    for plural in plurals {
+        let rule = if plural.ordinal {
+            plural::ordinal(locale.to_str()).ok_or_else(||
+                ParseError::OrdinalUnknownLocale(locale.to_str().into())
+            )?
+        } else {
+            plural::cardinal(locale.to_str()).ok_or_else(||
+                ParseError::PluralUnknownLocale(locale.to_str().into())
+            )?
+        };
+        let given = plural.categories.iter().map(|(c, _)| *c);
+
+        if let Some(category) = given
+        .clone()
+        .find(|c| !rule.categories().contains(c)) {
+            return Err(ParseError::PluralUnusedCategory(
+                plural.argument.clone(),
+                locale.to_str().into(),
+                category.to_string(),
+            ));
+        }
 
-            ':' if opened => formatting = true,
-
-            // Don't copy the formatting part
-            c if opened && !formatting => argument.push(
-                Name::validate_char(c)
-                .map_err(|_| ParseError::ArgBadChar(
-                    shorten(key), 
-                    shorten(&argument),
-                    c,
-                ))?
-            ),
-            
-            _ => (),
+        let missing = rule
+        .required()
+        .iter()
+        .filter(|c| !given.clone().any(|g| g == **c))
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+
+        if !missing.is_empty() {
+            return Err(ParseError::PluralMissingCategories(
+                plural.argument.clone(),
+                locale.to_str().into(),
+                missing,
+            ));
         }
     }
 
-    Ok(arguments) 
+    Ok(())
 }
 
 fn get_comment(
     comments: Vec<Option<String>>,
     key_comment: Option<String>,
+    inherited: &[(usize, usize)],
     locales: &[Name],
 ) -> Option<String> {
     let locale_comment = comments
@@ -414,6 +755,11 @@ fn get_comment(
     .filter_map(|(i, comment)| 
         comment.map(|c| format!("- *{}*: {c}\n", locales[i].to_str()))
     )
+    .chain(inherited.iter().map(|&(i, from)| format!(
+        "- *{}*: inherited from *{}*\n", 
+        locales[i].to_str(), 
+        locales[from].to_str(),
+    )))
     .collect::<String>();
 
     if locale_comment.is_empty() { return key_comment; }
@@ -428,7 +774,7 @@ fn get_entries(
     entries: Vec<Option<Entry>>,
     id: &Name,
     locales: &[Name],
-) -> Result<(Vec<String>, Vec<Option<String>>), ParseError> {
+) -> Result<Vec<Entry>, ParseError> {
     if entries.len() < locales.len() {
         return Err(ParseError::EntryMissingLocale(
             shorten(id), 
@@ -443,12 +789,7 @@ fn get_entries(
         shorten(id),
         locales[i].to_str().to_string()
     )))
-    .collect::<Result<Vec<Entry>,_>>()
-    .map(|ok| ok
-        .into_iter()
-        .map(|e| (e.value, e.comment))
-        .unzip()
-    )
+    .collect()
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -456,11 +797,18 @@ pub struct Key {
     pub id: Name,
     pub arguments: Vec<String>,
     pub comment: Option<String>,
-    pub entries: Vec<String>,
+    pub entries: Vec<Message>,
+    /// Locales that inherit their entry, and the locale it is from.
+    pub inherited: Vec<(usize, usize)>,
+    /// Locales that take their entry from a fallback, and the locale it is 
+    /// from.
+    pub fallbacks: Vec<(usize, usize)>,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 struct Entry {
     pub value: String,
     pub comment: Option<String>,
+    pub location: Location,
+    pub syntax: Syntax,
 }
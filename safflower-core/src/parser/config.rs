@@ -1,17 +1,68 @@
 use std::path::PathBuf;
 
-use crate::{error::Error, name::Name, parser::ParseError};
+use crate::{name::Name, parser::ParseError};
+
+/// How seriously to take something that is allowed, but suspicious.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Warn,
+    Deny,
+    /// A warning in debug builds, but an error in release builds.
+    DenyRelease,
+}
+
+/// Which way a locale's text is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+/// How values are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Syntax {
+    /// Templates for `format!`, with plurals and selects.
+    #[default]
+    Format,
+    /// ICU message format, with apostrophe quoting.
+    Icu,
+}
 
 pub struct Configuration {
     pub current_path: PathBuf,
     pub locales: Vec<Name>,
+    /// The locale each regional locale inherits its entries from.
+    pub parents: Vec<(Name, Name)>,
+    /// The name of each locale in its own language, if it has been given.
+    pub native_names: Vec<(Name, String)>,
+    /// The direction of each locale given one.
+    pub directions: Vec<(Name, Direction)>,
+    /// The locales to take missing entries of a locale from, in order.
+    pub fallbacks: Vec<(Name, Vec<Name>)>,
+    /// What to do about entries that do not use all of a key's arguments.
+    pub unused_arguments: Level,
+    /// What to do about entries taken from a fallback locale.
+    pub fallback: Level,
+    /// How the values that follow are written, until the end of the file.
+    pub syntax: Syntax,
     pub path_queue: Vec<PathBuf>,
+    /// Every path read or queued so far, to catch duplicates.
+    pub included: Vec<PathBuf>,
 }
 impl Configuration {
-    pub const fn new(root: PathBuf) -> Self {
+    pub fn new(root: PathBuf) -> Self {
         Self { 
+            included: vec![root.clone()],
             current_path: root, 
             locales: Vec::new(),
+            parents: Vec::new(),
+            native_names: Vec::new(),
+            directions: Vec::new(),
+            fallbacks: Vec::new(),
+            unused_arguments: Level::Deny,
+            fallback: Level::Warn,
+            syntax: Syntax::default(),
             path_queue: Vec::new(),
         }
     }
@@ -21,29 +72,30 @@ impl Configuration {
     /// # Errors
     /// If the line is empty or contains an unrecognised key, or if there is 
     /// an error in the specific command.
-    pub fn parse_config(&mut self, line: &str) -> Result<(), Error> {
+    pub fn parse_config(&mut self, line: &str) -> Result<(), ParseError> {
         let mut parts = line.split_whitespace();
         let Some(key) = parts.next() else { 
-            return Err(Error::Parse(
-                self.current_path.clone(),
-                ParseError::ConfigEmptyKey,
-            ))
+            return Err(ParseError::ConfigEmptyKey)
         };
 
         let values = parts.collect::<Vec<_>>();
 
         match key {
-            "locales" => self.locales(values)?,
+            "locales" => self.locales(values),
+            "locale-name" => self.locale_name(
+                // The name may have spaces, so it is taken as a whole
+                line.trim().strip_prefix(key).unwrap_or_default()
+            ),
+            "direction" => self.direction(values),
+            "fallback" => self.fallback(values),
+            "syntax" => self.syntax(&values),
             "include" => self.include(values),
-            
+            "warn" => self.lint(values, Level::Warn),
+            "deny" => self.lint(values, Level::Deny),
+            "deny-release" => self.lint(values, Level::DenyRelease),
 
-            k => return Err(Error::Parse(
-                self.current_path.clone(),
-                ParseError::ConfigUnknownKey(k.to_string()),
-            )),
+            k => Err(ParseError::ConfigUnknownKey(k.to_string())),
         }
-
-        Ok(())
     }
 
     pub fn find_locale(&self, locale: &Name) -> Option<usize> {
@@ -52,24 +104,31 @@ impl Configuration {
         .position(|l| l == locale)
     }
     
+    /// Declares locales. A locale may inherit the entries of one declared 
+    /// before it, as in `en-gb:en`.
+    /// 
     /// # Errors
-    /// Not having any locales, or inserting the same value twice.
-    pub fn locales(&mut self, parts: Vec<&str>) -> Result<(), Error> {
+    /// Not having any locales, inserting the same value twice, or inheriting
+    /// from an undeclared locale.
+    pub fn locales(&mut self, parts: Vec<&str>) -> Result<(), ParseError> {
         if parts.is_empty() { 
-            return Err(Error::Parse(
-                self.current_path.clone(),
-                ParseError::ConfigMissingValues("locales")
-            )); 
+            return Err(ParseError::ConfigMissingValues("locales")); 
         }
 
         for part in parts {
-            let locale = Name::try_from(part)?;
+            let (locale, parent) = match part.split_once(':') {
+                Some((locale, parent)) => (locale, Some(parent)),
+                None => (part, None),
+            };
+            let locale = Name::try_from(locale)?;
 
             if self.locales.iter().any(|l| l==&locale) {
-                return Err(Error::Parse(
-                    self.current_path.clone(),
-                    ParseError::DuplicateLocale(locale.into())
-                ));
+                return Err(ParseError::DuplicateLocale(locale.into()));
+            }
+
+            if let Some(parent) = parent {
+                let parent = self.declared_locale(parent)?;
+                self.parents.push((locale.clone(), parent));
             }
 
             self.locales.push(locale);
@@ -77,11 +136,184 @@ impl Configuration {
 
         Ok(())
     }
+
+    /// Gives the index of the locale a locale inherits from, if any.
+    pub fn parent(&self, locale: usize) -> Option<usize> {
+        self.parents
+        .iter()
+        .find(|(l, _)| l == &self.locales[locale])
+        .and_then(|(_, parent)| self.find_locale(parent))
+    }
     
+    /// Names a locale in its own language, as in `it "Italiano"`. The quotes
+    /// are optional.
+    /// 
+    /// # Errors
+    /// Missing the locale or name, an undeclared locale, or naming a locale
+    /// twice.
+    pub fn locale_name(&mut self, line: &str) -> Result<(), ParseError> {
+        let Some((locale, name)) = line.trim().split_once(char::is_whitespace)
+        else {
+            return Err(ParseError::ConfigMissingValues("locale-name"));
+        };
+
+        let name = name.trim();
+        let name = name
+            .strip_prefix('"')
+            .and_then(|n| n.strip_suffix('"'))
+            .unwrap_or(name);
+        if name.is_empty() {
+            return Err(ParseError::ConfigMissingValues("locale-name"));
+        }
+
+        let locale = self.declared_locale(locale)?;
+        if self.native_names.iter().any(|(l, _)| l == &locale) {
+            return Err(ParseError::ConfigDuplicate("locale-name", locale.into()));
+        }
+
+        self.native_names.push((locale, name.to_string()));
+        Ok(())
+    }
+
+    /// Sets the direction, `ltr` or `rtl`, of one or more locales, as in 
+    /// `ar he rtl`.
+    /// 
+    /// # Errors
+    /// Missing the locales or direction, an unrecognised direction, an 
+    /// undeclared locale, or setting the direction of a locale twice.
+    pub fn direction(&mut self, mut values: Vec<&str>) -> Result<(), ParseError> {
+        let direction = match values.pop() {
+            Some("ltr") => Direction::Ltr,
+            Some("rtl") => Direction::Rtl,
+            Some(d) => return Err(ParseError::ConfigInvalidValue(
+                "direction", 
+                d.to_string(),
+            )),
+            None => return Err(ParseError::ConfigMissingValues("direction")),
+        };
+        if values.is_empty() {
+            return Err(ParseError::ConfigMissingValues("direction"));
+        }
+
+        for locale in values {
+            let locale = self.declared_locale(locale)?;
+            if self.directions.iter().any(|(l, _)| l == &locale) {
+                return Err(ParseError::ConfigDuplicate(
+                    "direction", 
+                    locale.into(),
+                ));
+            }
+
+            self.directions.push((locale, direction));
+        }
+
+        Ok(())
+    }
+
+    /// Sets the locales to take missing entries of a locale from, in order,
+    /// as in `de-ch de en`.
+    /// 
+    /// # Errors
+    /// Missing the locale or its fallbacks, an undeclared locale, a locale
+    /// falling back to itself, or setting the fallbacks of a locale twice.
+    pub fn fallback(&mut self, values: Vec<&str>) -> Result<(), ParseError> {
+        let mut values = values.into_iter();
+        let Some(locale) = values.next() else {
+            return Err(ParseError::ConfigMissingValues("fallback"));
+        };
+
+        let locale = self.declared_locale(locale)?;
+        let chain = values
+        .map(|l| self.declared_locale(l))
+        .collect::<Result<Vec<_>, _>>()?;
+
+        if chain.is_empty() {
+            return Err(ParseError::ConfigMissingValues("fallback"));
+        }
+        if chain.contains(&locale) {
+            return Err(ParseError::ConfigInvalidValue(
+                "fallback", 
+                locale.into(),
+            ));
+        }
+        if self.fallbacks.iter().any(|(l, _)| l == &locale) {
+            return Err(ParseError::ConfigDuplicate("fallback", locale.into()));
+        }
+
+        self.fallbacks.push((locale, chain));
+        Ok(())
+    }
+
+    /// Gives the indices of the locales a locale falls back to, in order.
+    pub fn fallback_chain(&self, locale: usize) -> Vec<usize> {
+        self.fallbacks
+        .iter()
+        .find(|(l, _)| l == &self.locales[locale])
+        .map(|(_, chain)| chain
+            .iter()
+            .filter_map(|l| self.find_locale(l))
+            .collect()
+        )
+        .unwrap_or_default()
+    }
+
+    /// Sets how the values that follow are written, `format` or `icu`, for 
+    /// the rest of the file.
+    /// 
+    /// # Errors
+    /// Not having exactly one syntax, or an unrecognised one.
+    pub fn syntax(&mut self, values: &[&str]) -> Result<(), ParseError> {
+        self.syntax = match values {
+            ["format"] => Syntax::Format,
+            ["icu"] => Syntax::Icu,
+            [] => return Err(ParseError::ConfigMissingValues("syntax")),
+            values => return Err(ParseError::ConfigInvalidValue(
+                "syntax", 
+                values.join(" "),
+            )),
+        };
+
+        Ok(())
+    }
+
+    /// Reads the name of a locale, which must have been declared already.
+    fn declared_locale(&self, locale: &str) -> Result<Name, ParseError> {
+        let locale = Name::try_from(locale)?;
+
+        match self.find_locale(&locale) {
+            Some(_) => Ok(locale),
+            None => Err(ParseError::UndeclaredLocale(locale.into())),
+        }
+    }
+
+    /// Sets the level of one or more lints.
+    /// 
+    /// # Errors
+    /// Not having any lints, or an unrecognised one.
+    pub fn lint(
+        &mut self, 
+        lints: Vec<&str>, 
+        level: Level,
+    ) -> Result<(), ParseError> {
+        if lints.is_empty() { 
+            return Err(ParseError::ConfigMissingValues("lint")); 
+        }
+
+        for lint in lints {
+            match lint {
+                "unused-arguments" => self.unused_arguments = level,
+                "fallback" => self.fallback = level,
+                l => return Err(ParseError::ConfigUnknownLint(l.to_string())),
+            }
+        }
+
+        Ok(())
+    }
+
     /// The number of locales declared.
     pub const fn locale_count(&self) -> usize { self.locales.len() }
     
-    fn include(&mut self, values: Vec<&str>) {
+    fn include(&mut self, values: Vec<&str>) -> Result<(), ParseError> {
         let parent = self.current_path.parent();
         let new_paths = values
         .into_iter()
@@ -93,10 +325,20 @@ impl Configuration {
             } else { 
                 path
             }
-        );
+        )
+        .collect::<Vec<_>>();
+
+        for path in &new_paths {
+            if self.included.contains(path) {
+                return Err(ParseError::ConfigDuplicateFile(path.clone()));
+            }
+            self.included.push(path.clone());
+        }
 
         let old_queue = std::mem::take(&mut self.path_queue);
-        self.path_queue = new_paths.chain(old_queue).collect();
+        self.path_queue = new_paths.into_iter().chain(old_queue).collect();
+
+        Ok(())
     }
     
     pub fn pop_path(&mut self) -> Option<PathBuf> {
@@ -104,6 +346,7 @@ impl Configuration {
 
         if let Some(path) = &path {
             self.current_path.clone_from(path);
+            self.syntax = Syntax::default();
         }
 
         path
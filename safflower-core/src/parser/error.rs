@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 use thiserror::Error;
 
-use crate::reader::Token;
+use crate::reader::{ReadError, Token};
 
 #[derive(Error, Debug)]
 pub enum ParseError {
@@ -9,10 +9,18 @@ pub enum ParseError {
     ConfigEmptyKey,
     #[error("unrecognised key \"{0}\"")]
     ConfigUnknownKey(String),
+    #[error("unrecognised lint \"{0}\"")]
+    ConfigUnknownLint(String),
     #[error("missing values for config \"{0}\"")]
     ConfigMissingValues(&'static str),
+    #[error("invalid value \"{1}\" for config \"{0}\"")]
+    ConfigInvalidValue(&'static str, String),
+    #[error("config \"{0}\" is given more than once for locale \"{1}\"")]
+    ConfigDuplicate(&'static str, String),
     #[error("file \"{0}\" is included more than once")]
     ConfigDuplicateFile(PathBuf),
+    #[error(transparent)]
+    InvalidName(#[from] ReadError),
     
     #[error("duplicate locale \"{0}\"")]
     DuplicateLocale(String),
@@ -23,15 +31,56 @@ pub enum ParseError {
     #[error("entry \"{0}\" is missing locale [{1}]")]
     EntryMissingLocale(String, String),
 
+    #[error("locale \"{0}\" has no name, but other locales do; please add \
+        one with \"!locale-name {0} NAME\"")]
+    MissingLocaleName(String),
+
+    #[error("{0} entries are missing, and taken from fallback locales \
+        instead ({1})")]
+    FallbackUsed(usize, String),
+
     #[error("there are no locales set up; please do so with \"!locales \
         LOCALES+\"")]
     NoLocales,
 
     #[error("value contains nested or an unclosed opening brace '{{'")]
     NestedBrace,
+    #[error("value contains a branch that is not closed by '}}'")]
+    UnclosedBranch,
     #[error("value contains unopened closing brace '}}'")]
     ExtraClosingBrace,
 
+    #[error("unknown construct \"{0}\"; expected \"plural\", \"select\", \
+        or \"selectordinal\"")]
+    UnknownConstruct(String),
+    #[error("expected a branch such as \"other {{...}}\", but found \"{0}\"")]
+    ExpectedBranch(String),
+    #[error("branch \"{0}\" is given more than once")]
+    DuplicateBranch(String),
+    #[error("\"{0}\" has no \"other\" branch")]
+    MissingOther(String),
+    #[error("invalid plural selector \"{0}\"; expected \"zero\", \"one\", \
+        \"two\", \"few\", \"many\", \"other\", or an exact count like \"=0\"")]
+    PluralBadSelector(String),
+    #[error("select branch \"{0}\" contains invalid char \"{1}\", but must \
+        be only alphanumeric, '-', or '_'")]
+    SelectBadSelector(String, char),
+    #[error("\"{0}\" is not supported in ICU syntax")]
+    IcuUnsupported(String),
+    #[error("braces are quoted as '{{' and '}}' in ICU syntax, rather than \
+        escaped with a backslash")]
+    IcuBraceEscape,
+    #[error("no plural rules are known for locale \"{0}\"")]
+    PluralUnknownLocale(String),
+    #[error("no ordinal rules are known for locale \"{0}\"")]
+    OrdinalUnknownLocale(String),
+    #[error("plural of \"{0}\" is missing categories {2:?}, which locale \
+        \"{1}\" needs")]
+    PluralMissingCategories(String, String, Vec<String>),
+    #[error("plural of \"{0}\" has category \"{2}\", which locale \"{1}\" \
+        does not use")]
+    PluralUnusedCategory(String, String, String),
+
     #[error("line \"{0}\" contains argument \"{1}\" with invalid char \
         \"{2}\", but must be only alphanumeric, '-', or '_'")]
     ArgBadChar(String, String, char),
@@ -40,9 +89,9 @@ pub enum ParseError {
         digits")]
     ArgBadStart(String, String, char),
 
-    #[error("entry \"{1}\" for key \"{0}\" has arguments {2:?}, which \
-        does not match {3:?} from the key's first entry")]
-    ArgumentMismatch(String, String, Vec<String>, Vec<String>),
+    #[error("entry \"{1}\" for key \"{0}\" does not use arguments {2:?}, \
+        which other entries of the key use")]
+    ArgumentUnused(String, String, Vec<String>),
     #[error("line may not start with {0}")]
     UnexpectedToken(Token),
     #[error("expected locale to follow, but token stream ended")]
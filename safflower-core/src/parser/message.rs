@@ -0,0 +1,578 @@
+use std::{iter::Peekable, str::Chars};
+
+use crate::{name::Name, plural::Category, shorten};
+use super::{ParseError, Syntax};
+
+/// A value, as the text and constructs it is made of.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Message(pub Vec<Part>);
+impl Message {
+    /// Gives the template of a message that is only text.
+    #[must_use]
+    pub fn as_text(&self) -> Option<&str> {
+        match self.0.as_slice() {
+            [] => Some(""),
+            [Part::Text(text)] => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Gives every plural in the message, including those in branches.
+    #[must_use]
+    pub fn plurals(&self) -> Vec<&Plural> {
+        self
+        .constructs()
+        .into_iter()
+        .filter_map(|part| match part {
+            Part::Plural(plural) => Some(plural),
+            _ => None,
+        })
+        .collect()
+    }
+
+    /// Gives every select in the message, including those in branches.
+    #[must_use]
+    pub fn selects(&self) -> Vec<&Select> {
+        self
+        .constructs()
+        .into_iter()
+        .filter_map(|part| match part {
+            Part::Select(select) => Some(select),
+            _ => None,
+        })
+        .collect()
+    }
+
+    /// Gives the arguments written out by the text of the message, by their
+    /// names in the templates.
+    #[must_use]
+    pub fn displayed(&self) -> Vec<&str> {
+        let mut displayed = Vec::new();
+
+        for part in &self.0 {
+            match part {
+                Part::Text(text) => displayed.extend(placeholders(text)),
+                part => for message in part.branches() {
+                    displayed.append(&mut message.displayed());
+                },
+            }
+        }
+
+        displayed
+    }
+
+    /// Gives every part that is not text, including those in branches.
+    fn constructs(&self) -> Vec<&Part> {
+        let mut constructs = Vec::new();
+
+        for part in &self.0 {
+            if matches!(part, Part::Text(_)) { continue; }
+            constructs.push(part);
+
+            for message in part.branches() {
+                constructs.append(&mut message.constructs());
+            }
+        }
+
+        constructs
+    }
+}
+impl From<&str> for Message {
+    fn from(text: &str) -> Self {
+        if text.is_empty() { return Self::default(); }
+        Self(vec![Part::Text(text.to_owned())])
+    }
+}
+impl From<String> for Message {
+    fn from(text: String) -> Self { Self::from(text.as_str()) }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Part {
+    /// A template for `format!`, where positional arguments are named
+    /// `argN`.
+    Text(String),
+    /// A message picked by the plural category of a count.
+    Plural(Plural),
+    /// A message picked by the value of an argument, e.g. a gender.
+    Select(Select),
+}
+impl Part {
+    /// Gives the message of every branch, of which text has none.
+    #[must_use]
+    pub fn branches(&self) -> Vec<&Message> {
+        match self {
+            Self::Text(_) => Vec::new(),
+            Self::Plural(plural) => plural.messages().collect(),
+            Self::Select(select) => select.branches
+                .iter()
+                .map(|(_, m)| m)
+                .collect(),
+        }
+    }
+}
+
+/// The branches of `{count, plural, =0 {...} one {...} other {...}}`, or of
+/// `{count, selectordinal, ...}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plural {
+    /// The argument counted, as it is in the arguments of the key.
+    pub argument: String,
+    /// Whether the categories are of ordinals, as in 1st and 2nd, rather than
+    /// of counts.
+    pub ordinal: bool,
+    /// Messages for exact counts, which go before the categories.
+    pub exact: Vec<(u64, Message)>,
+    /// Messages for plural categories, always including `other`.
+    pub categories: Vec<(Category, Message)>,
+}
+impl Plural {
+    /// Gives the message of every branch.
+    pub fn messages(&self) -> impl Iterator<Item = &Message> {
+        self.exact
+        .iter()
+        .map(|(_, m)| m)
+        .chain(self.categories.iter().map(|(_, m)| m))
+    }
+}
+
+/// The branches of `{gender, select, female {...} male {...} other {...}}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Select {
+    /// The argument selected on, as it is in the arguments of the key.
+    pub argument: String,
+    /// Messages for each value, as written, always including `other`.
+    pub branches: Vec<(String, Message)>,
+}
+
+/// Reads the arguments and constructs of a value.
+pub(super) struct MessageParser<'a> {
+    value: &'a str,
+    syntax: Syntax,
+    chars: Peekable<Chars<'a>>,
+    arguments: Vec<String>,
+    unnamed_indexer: usize,
+}
+impl<'a> MessageParser<'a> {
+    /// Reads a value as a message, where positional arguments are named
+    /// `argN`. This way the templates no longer depend on argument order,
+    /// and any locale may leave some arguments out. The arguments are given
+    /// in order of first appearance.
+    pub(super) fn parse(
+        value: &'a str,
+        syntax: Syntax,
+    ) -> Result<(Vec<String>, Message), ParseError> {
+        let mut parser = Self {
+            value,
+            syntax,
+            chars: value.chars().peekable(),
+            arguments: Vec::new(),
+            unnamed_indexer: 0,
+        };
+
+        let message = parser.message(false, None)?;
+        Ok((parser.arguments, message))
+    }
+
+    /// Reads a message, up to the end of the value or, for a branch, up to
+    /// its closing brace. Within the branches of a plural, `#` stands for
+    /// the count.
+    fn message(
+        &mut self,
+        branch: bool,
+        count: Option<&str>,
+    ) -> Result<Message, ParseError> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+
+        loop {
+            let Some(c) = self.chars.next() else {
+                if branch { return Err(ParseError::UnclosedBranch); }
+                break;
+            };
+
+            let format = self.syntax == Syntax::Format;
+
+            match c {
+                // The reader leaves a backslash before literal braces, `#`,
+                // and backslashes
+                '\\' => self.escape(&mut text)?,
+
+                // Literal braces are also escaped like in `format!`. A branch
+                // ends at its first closing brace, though, so there they
+                // must be escaped with a backslash.
+                '{' if format && self.chars.next_if_eq(&'{').is_some() => {
+                    text += "{{";
+                },
+                '}' if branch => break,
+                '}' if format && self.chars.next_if_eq(&'}').is_some() => {
+                    text += "}}";
+                },
+                '}' => return Err(ParseError::ExtraClosingBrace),
+
+                '\'' if !format => self.quote(&mut text, count.is_some())?,
+
+                '{' => match self.placeholder(count)? {
+                    Placeholder::Argument(template) => text += &template,
+                    Placeholder::Part(part) => {
+                        if !text.is_empty() {
+                            parts.push(Part::Text(std::mem::take(&mut text)));
+                        }
+                        parts.push(part);
+                    },
+                },
+
+                '#' => match count {
+                    Some(count) => {
+                        text.push('{');
+                        text += &template_name(count);
+                        text.push('}');
+                    },
+                    None => text.push('#'),
+                },
+
+                c => text.push(c),
+            }
+        }
+
+        if !text.is_empty() { parts.push(Part::Text(text)); }
+
+        Ok(Message(parts))
+    }
+
+    /// Reads what follows an apostrophe in ICU syntax. Two of them are one
+    /// apostrophe, and one before a brace (or `#` in a plural) quotes the 
+    /// text up to the next lone apostrophe. Any other is just an apostrophe.
+    fn quote(
+        &mut self,
+        text: &mut String,
+        plural: bool,
+    ) -> Result<(), ParseError> {
+        if self.chars.next_if_eq(&'\'').is_some() {
+            text.push('\'');
+            return Ok(());
+        }
+
+        let quoting = match self.chars.peek() {
+            Some('{' | '}' | '|') => true,
+            Some('#') => plural,
+            _ => false,
+        };
+        if !quoting {
+            text.push('\'');
+            return Ok(());
+        }
+
+        // Like in ICU, a quote that is never closed runs to the end
+        while let Some(c) = self.chars.next() {
+            match c {
+                '\'' if self.chars.next_if_eq(&'\'').is_some() => text.push('\''),
+                '\'' => return Ok(()),
+                '\\' => self.escape(text)?,
+                '{' | '}' => {
+                    text.push(c);
+                    text.push(c);
+                },
+                c => text.push(c),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads what follows a backslash, adding the literal char it escapes.
+    /// Braces are quoted instead in ICU syntax, so that strings stay as 
+    /// other ICU tools read them.
+    fn escape(&mut self, text: &mut String) -> Result<(), ParseError> {
+        match self.chars.next() {
+            Some('{' | '}') if self.syntax == Syntax::Icu => {
+                return Err(ParseError::IcuBraceEscape);
+            },
+            // Text is a template, where braces are doubled
+            Some(c @ ('{' | '}')) => {
+                text.push(c);
+                text.push(c);
+            },
+            Some(c) => text.push(c),
+            None => text.push('\\'),
+        }
+
+        Ok(())
+    }
+
+    /// Reads what follows an opening brace: either an argument, given back
+    /// as its template, or a construct such as a plural.
+    fn placeholder(
+        &mut self,
+        count: Option<&str>,
+    ) -> Result<Placeholder, ParseError> {
+        if self.syntax == Syntax::Icu { return self.icu_placeholder(count); }
+
+        let mut argument = String::new();
+        let mut spec = String::new();
+
+        loop {
+            match self.chars.next() {
+                None | Some('{') => return Err(ParseError::NestedBrace),
+                Some('}') => break,
+
+                Some(',') if spec.is_empty() => {
+                    let argument = self.argument(argument)?;
+                    return self.construct(argument, count);
+                },
+
+                // The formatting part is copied, but not checked
+                Some(c) if !spec.is_empty() || c == ':' => spec.push(c),
+
+                Some(c) => argument.push(
+                    Name::validate_char(c)
+                    .map_err(|_| ParseError::ArgBadChar(
+                        shorten(self.value),
+                        shorten(&argument),
+                        c,
+                    ))?
+                ),
+            }
+        }
+
+        let argument = self.argument(argument)?;
+        Ok(Placeholder::Argument(
+            format!("{{{}{spec}}}", template_name(&argument))
+        ))
+    }
+
+    /// Reads what follows an opening brace in ICU syntax, where there are no
+    /// format specs, but there may be whitespace around the argument.
+    fn icu_placeholder(
+        &mut self,
+        count: Option<&str>,
+    ) -> Result<Placeholder, ParseError> {
+        self.skip_whitespace();
+
+        let mut argument = String::new();
+        while let Some(c) = self.chars.next_if(|c| 
+            !c.is_whitespace() && !matches!(c, ',' | '}')
+        ) {
+            argument.push(
+                Name::validate_char(c)
+                .map_err(|_| ParseError::ArgBadChar(
+                    shorten(self.value),
+                    shorten(&argument),
+                    c,
+                ))?
+            );
+        }
+
+        self.skip_whitespace();
+
+        let argument = match self.chars.next() {
+            Some('}') => self.argument(argument)?,
+            Some(',') => {
+                let argument = self.argument(argument)?;
+                return self.construct(argument, count);
+            },
+            _ => return Err(ParseError::NestedBrace),
+        };
+
+        Ok(Placeholder::Argument(
+            format!("{{{}}}", template_name(&argument))
+        ))
+    }
+
+    /// Checks the name of an argument, numbering it if it has none, and
+    /// notes it as one of the arguments of the value.
+    fn argument(&mut self, mut argument: String) -> Result<String, ParseError> {
+        if argument.is_empty() {
+            argument = self.unnamed_indexer.to_string();
+            self.unnamed_indexer += 1;
+        }
+        else if !argument.starts_with(|c: char| c.is_ascii_alphabetic())
+        && !argument.chars().all(char::is_numeric) {
+            return Err(ParseError::ArgBadStart(
+                self.value.to_string(),
+                shorten(&argument),
+                argument.chars().next().unwrap_or_default(),
+            ));
+        }
+
+        if !self.arguments.contains(&argument) {
+            self.arguments.push(argument.clone());
+        }
+
+        Ok(argument)
+    }
+
+    /// Reads the rest of a construct, such as `plural, one {...} other {...}}`.
+    /// Branches of a select keep the count of the plural they are in.
+    fn construct(
+        &mut self,
+        argument: String,
+        count: Option<&str>,
+    ) -> Result<Placeholder, ParseError> {
+        let mut kind = String::new();
+        let closed = loop {
+            match self.chars.next() {
+                Some(',') => break false,
+                Some('}') => break true,
+                Some(c) => kind.push(c),
+                None => {
+                    return Err(ParseError::ExpectedBranch(shorten(kind.trim())));
+                },
+            }
+        };
+        let icu = self.syntax == Syntax::Icu;
+
+        match (kind.trim(), closed) {
+            ("plural", false) => self
+                .plural(argument, false)
+                .map(|p| Placeholder::Part(Part::Plural(p))),
+            ("selectordinal", false) => self
+                .plural(argument, true)
+                .map(|p| Placeholder::Part(Part::Plural(p))),
+            ("select", false) => self
+                .select(argument, count)
+                .map(|s| Placeholder::Part(Part::Select(s))),
+
+            // Numbers are written as they display
+            ("number", true) if icu => Ok(Placeholder::Argument(
+                format!("{{{}}}", template_name(&argument))
+            )),
+            (kind @ ("number" | "date" | "time" | "spellout" | "ordinal" 
+            | "duration"), _) if icu => {
+                Err(ParseError::IcuUnsupported(kind.to_owned()))
+            },
+
+            (kind @ ("plural" | "select" | "selectordinal"), true) => {
+                Err(ParseError::ExpectedBranch(kind.to_owned()))
+            },
+            (kind, _) => Err(ParseError::UnknownConstruct(shorten(kind))),
+        }
+    }
+
+    /// Reads the branches of a plural, up to its closing brace.
+    fn plural(
+        &mut self,
+        argument: String,
+        ordinal: bool,
+    ) -> Result<Plural, ParseError> {
+        let mut plural = Plural {
+            argument,
+            ordinal,
+            exact: Vec::new(),
+            categories: Vec::new(),
+        };
+
+        while let Some(selector) = self.selector()? {
+            let message = self.message(true, Some(&plural.argument))?;
+
+            if let Some(count) = selector.strip_prefix('=') {
+                let count = count
+                .parse()
+                .map_err(|_| ParseError::PluralBadSelector(shorten(&selector)))?;
+
+                if plural.exact.iter().any(|(c, _)| *c == count) {
+                    return Err(ParseError::DuplicateBranch(selector));
+                }
+                plural.exact.push((count, message));
+            } else {
+                let category = Category::parse(&selector).ok_or_else(|| 
+                    ParseError::PluralBadSelector(shorten(&selector))
+                )?;
+
+                if plural.categories.iter().any(|(c, _)| *c == category) {
+                    return Err(ParseError::DuplicateBranch(selector));
+                }
+                plural.categories.push((category, message));
+            }
+        }
+
+        if !plural.categories.iter().any(|(c, _)| *c == Category::Other) {
+            return Err(ParseError::MissingOther(plural.argument));
+        }
+
+        Ok(plural)
+    }
+
+    /// Reads the branches of a select, up to its closing brace.
+    fn select(
+        &mut self,
+        argument: String,
+        count: Option<&str>,
+    ) -> Result<Select, ParseError> {
+        let mut branches = Vec::<(String, Message)>::new();
+
+        while let Some(selector) = self.selector()? {
+            if let Some(c) = selector
+            .chars()
+            .find(|c| !c.is_alphanumeric() && !matches!(c, '-' | '_')) {
+                return Err(ParseError::SelectBadSelector(shorten(&selector), c));
+            }
+            if branches.iter().any(|(s, _)| *s == selector) {
+                return Err(ParseError::DuplicateBranch(selector));
+            }
+
+            let message = self.message(true, count)?;
+            branches.push((selector, message));
+        }
+
+        if !branches.iter().any(|(s, _)| s == "other") {
+            return Err(ParseError::MissingOther(argument));
+        }
+
+        Ok(Select { argument, branches })
+    }
+
+    /// Reads the selector of the next branch and its opening brace, or the
+    /// closing brace of the construct.
+    fn selector(&mut self) -> Result<Option<String>, ParseError> {
+        self.skip_whitespace();
+
+        let mut selector = String::new();
+        while let Some(c) = self.chars.next_if(|c|
+            !c.is_whitespace() && !matches!(c, '{' | '}')
+        ) {
+            selector.push(c);
+        }
+
+        if self.syntax == Syntax::Icu && selector.starts_with("offset:") {
+            return Err(ParseError::IcuUnsupported(String::from("offset")));
+        }
+
+        self.skip_whitespace();
+
+        match self.chars.next() {
+            Some('}') if selector.is_empty() => Ok(None),
+            Some('{') if !selector.is_empty() => Ok(Some(selector)),
+            None => Err(ParseError::NestedBrace),
+            _ => Err(ParseError::ExpectedBranch(shorten(&selector))),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+}
+
+enum Placeholder {
+    Argument(String),
+    Part(Part),
+}
+
+/// Gives the arguments of a template, by their names.
+fn placeholders(template: &str) -> impl Iterator<Item = &str> {
+    // Templates are well-formed, so any brace not doubled opens an argument
+    template
+    .split("{{")
+    .flat_map(|part| part.split('{').skip(1))
+    .filter_map(|placeholder| placeholder.split(['}', ':']).next())
+}
+
+/// Gives the name an argument has in templates, where positional arguments
+/// are named `argN`.
+fn template_name(argument: &str) -> String {
+    if argument.chars().all(char::is_numeric) {
+        format!("arg{argument}")
+    } else {
+        argument.to_owned()
+    }
+}
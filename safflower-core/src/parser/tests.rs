@@ -1,7 +1,7 @@
-use crate::{name::Name, reader::Token};
+use crate::{name::Name, plural::Category, reader::Token};
 use super::*;
 
-fn parse(tokens: Vec<Token>) -> Result<Vec<Key>, Error> {
+fn parse(tokens: Vec<Token>) -> Result<Vec<Key>, Vec<Error>> {
     Parser::from_vec(tokens)
     .parse()
     .map(|pd| pd.keys)
@@ -17,6 +17,14 @@ fn names<const S: usize>(strs: [&str; S]) -> Vec<Name> {
     .unwrap()
 }
 
+fn first_error(source: &str) -> String {
+    let errors = Parser::from_text(source).parse().expect_err("should be err");
+    let Error::Parse(_, error) = &errors[0] else {
+        panic!("should be a parse error, got {errors:?}")
+    };
+    error.to_string()
+}
+
 #[test]
 fn bad_locales() {
     let ins = [
@@ -80,7 +88,9 @@ fn minimal_case() {
                 id: name("key"), 
                 arguments: vec![],
                 comment: None, 
-                entries: vec![String::from("value")] 
+                entries: vec![Message::from("value")],
+                inherited: vec![], 
+                fallbacks: vec![], 
             }
         ]
     );
@@ -106,7 +116,9 @@ fn key_comment() {
                 id: name("key"), 
                 arguments: vec![],
                 comment: Some(String::from("hi!")), 
-                entries: vec![String::from("value")] 
+                entries: vec![Message::from("value")],
+                inherited: vec![], 
+                fallbacks: vec![], 
             }
         ]
     );
@@ -145,7 +157,9 @@ fn entry_comments() {
                 id: name("key"), 
                 arguments: vec![],
                 comment: Some(String::from(" # Locale notes\n- *a*: hi!\n")), 
-                entries: vec![String::from("value")] 
+                entries: vec![Message::from("value")],
+                inherited: vec![], 
+                fallbacks: vec![], 
             }
         ]
     );
@@ -173,9 +187,11 @@ fn mutli_locales() {
                 arguments: vec![],
                 comment: None, 
                 entries: vec![
-                    String::from("value A"),
-                    String::from("value B"), 
-                ] 
+                    Message::from("value A"),
+                    Message::from("value B"), 
+                ],
+                inherited: vec![], 
+                fallbacks: vec![], 
             }
         ]
     );
@@ -320,19 +336,735 @@ fn separate_key() {
                 arguments: vec![],
                 comment: None, 
                 entries: vec![
-                    String::from("value A"),
-                    String::from("value B"), 
-                ] 
+                    Message::from("value A"),
+                    Message::from("value B"), 
+                ],
+                inherited: vec![], 
+                fallbacks: vec![], 
             }, 
             Key { 
                 id: name("key2"), 
                 arguments: vec![],
                 comment: None, 
                 entries: vec![
-                    String::from("value A"),
-                    String::from("value B"), 
-                ] 
+                    Message::from("value A"),
+                    Message::from("value B"), 
+                ],
+                inherited: vec![], 
+                fallbacks: vec![], 
             }
         ]
     );
 }
+
+#[test]
+fn read_paths() {
+    let parsed = Parser::new("../test-data/separate_files.txt")
+    .expect("should be readable")
+    .parse()
+    .expect("should be ok");
+
+    assert_eq!(
+        parsed.paths,
+        vec![
+            PathBuf::from("../test-data/separate_files.txt"),
+            PathBuf::from("../test-data/separation/file_a.txt"),
+            PathBuf::from("../test-data/separation/file_b.txt"),
+        ]
+    );
+}
+
+#[test]
+fn error_location() {
+    let source = "!locales en\nkey:\n    en \"a\"\n    fr \"b\"\n";
+    let errors = Parser::from_text(source)
+    .parse()
+    .expect_err("should be err");
+
+    assert_eq!(
+        errors[0].to_string(),
+        "parsing error: encountered locale \"fr\", but it has not been \
+        declared\n --> string:4:5\n  |\n4 |     fr \"b\"\n  |     ^",
+    );
+}
+
+#[test]
+fn validation_error_location() {
+    let source = "!locales en it\nkey:\n  en \"{a} {b}\"\n  it \"{b}\"";
+    let errors = Parser::from_text(source).parse().expect_err("should be err");
+    let [Error::Parse(location, ParseError::ArgumentUnused(..))] = 
+        errors.as_slice()
+    else { panic!("should be an unused argument, got {errors:?}") };
+
+    assert_eq!((location.line, location.column), (4, 6));
+    assert_eq!(location.snippet, "  it \"{b}\"");
+}
+
+#[test]
+fn all_errors() {
+    let source = "
+        !locales en it
+        !unknown
+        a:
+            en \"a\"
+            fr \"a\"
+        b:
+            en \"b\" it \"b\"
+        c$:
+            en \"c\"
+            it \"c\"
+        d:
+            en \"d\"
+        e:
+            en \"{e}\"
+            it \"{f}\"
+    ";
+
+    let errors = Parser::from_text(source).parse().expect_err("should be err");
+    let lines = errors
+    .iter()
+    .map(|e| match e {
+        Error::Read(l, _) | Error::Parse(l, _) => l.line,
+        Error::Io(..) => 0,
+    })
+    .collect::<Vec<_>>();
+
+    // The entry of "a" with an undeclared locale makes "a" fail, so it does 
+    // not also report the missing "it"
+    assert_eq!(lines, vec![3, 6, 9, 12, 15], "{errors:#?}");
+}
+
+#[test]
+fn adjacent_bad_keys() {
+    let source = "
+        !locales en
+        a:
+            en \"{a\"
+        b1 en \"b\"
+        c:
+            en \"c}\"
+        d$:
+            en \"d\"
+    ";
+
+    let errors = Parser::from_text(source).parse().expect_err("should be err");
+    let mut lines = errors
+    .iter()
+    .map(|e| match e {
+        Error::Read(l, _) | Error::Parse(l, _) => l.line,
+        Error::Io(..) => 0,
+    })
+    .collect::<Vec<_>>();
+    lines.sort_unstable();
+
+    // A broken line belongs to the key it starts, not to the one before
+    assert_eq!(lines, vec![4, 5, 7, 8], "{errors:#?}");
+}
+
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn template_positional() {
+    for (line, template) in [
+        ("Hello {name}", "Hello {name}"),
+        ("{0} is really cool", "{arg0} is really cool"),
+        ("{arg-b}", "{arg_b}"),
+        ("{}, {}", "{arg0}, {arg1}"),
+        ("{:.2} and {1:>4}", "{arg0:.2} and {arg1:>4}"),
+    ] {
+        let (_, result) = MessageParser::parse(line, Syntax::Format)
+        .unwrap();
+        assert_eq!(result, Message::from(template));
+    }
+}
+
+#[test]
+fn reordered_arguments() {
+    let source = "
+        !locales en de
+        key:
+            en \"{a} then {b}\"
+            de \"{b} dann {a}\"
+    ";
+    let keys = Parser::from_text(source).parse().unwrap().keys;
+
+    assert_eq!(keys[0].arguments, vec!["a", "b"]);
+}
+
+#[test]
+fn unused_arguments() {
+    let source = "
+        !locales en de
+        key:
+            en \"{a} then {b}\"
+            de \"{b}\"
+    ";
+    let errors = Parser::from_text(source).parse().expect_err("should be err");
+    assert!(
+        matches!(errors[..], [Error::Parse(_, ParseError::ArgumentUnused(..))]),
+        "{errors:?}",
+    );
+
+    let parsed = Parser::from_text(&format!("!warn unused-arguments{source}"))
+    .parse()
+    .expect("should be ok");
+    
+    assert_eq!(parsed.keys[0].arguments, vec!["a", "b"]);
+    assert_eq!(parsed.warnings.len(), 1);
+}
+
+#[test]
+fn template_escaped_braces() {
+    for (line, template, args) in [
+        ("{{}}", "{{}}", vec![]),
+        ("{{\"a\": {a}}}", "{{\"a\": {a}}}", vec!["a"]),
+        ("{{{0}}}", "{{{arg0}}}", vec!["0"]),
+        (r"a \\ b", r"a \ b", vec![]),
+        (r"\{a\} \#", "{{a}} #", vec![]),
+    ] {
+        let (arguments, result) = MessageParser::parse(line, Syntax::Format)
+        .unwrap();
+        assert_eq!(result, Message::from(template), "src: '{line}'");
+        assert_eq!(arguments, args, "src: '{line}'");
+    }
+}
+
+#[test]
+fn locale_metadata() {
+    let source = "
+        !locales en ar pt-BR
+        !locale-name en English
+        !locale-name ar \"العربية\"
+        !locale-name pt-BR \"Português do Brasil\"
+        !direction ar rtl
+        key:
+            en \"a\"
+            ar \"b\"
+            pt-BR \"c\"
+    ";
+    let parsed = Parser::from_text(source).parse().expect("should be ok");
+
+    assert_eq!(
+        parsed.native_names,
+        Some(vec![
+            String::from("English"), 
+            String::from("العربية"), 
+            String::from("Português do Brasil"),
+        ]),
+    );
+    assert_eq!(
+        parsed.directions, 
+        vec![Direction::Ltr, Direction::Rtl, Direction::Ltr],
+    );
+}
+
+#[test]
+fn bad_locale_metadata() {
+    for (line, expected) in [
+        ("!locale-name it", "missing values for config \"locale-name\""),
+        ("!locale-name it \"\"", "missing values for config \"locale-name\""),
+        ("!locale-name fr French", "encountered locale \"fr\", but it has \
+            not been declared"),
+        ("!locale-name en A\n!locale-name en B", "config \"locale-name\" is \
+            given more than once for locale \"en\""),
+        ("!direction rtl", "missing values for config \"direction\""),
+        ("!direction it up", "invalid value \"up\" for config \"direction\""),
+    ] {
+        let source = format!("!locales en it\n{line}\nkey:\n en \"a\"\n it \"b\"");
+        assert_eq!(first_error(&source), expected, "src: {line}");
+    }
+}
+
+#[test]
+fn missing_locale_name() {
+    let source = "!locales en\n!locales it\n!locale-name en English\n";
+    let errors = Parser::from_text(source).parse().expect_err("should be err");
+    let [Error::Parse(location, ParseError::MissingLocaleName(locale))] = 
+        errors.as_slice()
+    else { panic!("should be a missing name, got {errors:?}") };
+
+    assert_eq!(locale, "it");
+    assert_eq!(location.line, 2);
+}
+
+#[test]
+fn fallback_entries() {
+    let source = "
+        !locales en de de-ch it
+        !fallback de-ch de en
+        !fallback it en
+        a:
+            en \"a\"
+            de \"A\"
+        b:
+            en \"b\"
+            de \"B\"
+            it \"bb\"
+    ";
+    let parsed = Parser::from_text(source).parse().expect("should be ok");
+
+    assert_eq!(parsed.keys[0].entries, ["a", "A", "A", "a"].map(Message::from));
+    assert_eq!(parsed.keys[1].entries, ["b", "B", "B", "bb"].map(Message::from));
+
+    let [Error::Parse(location, error)] = parsed.warnings.as_slice() else {
+        panic!("should be one warning, got {:?}", parsed.warnings)
+    };
+    assert_eq!(location.line, 5);
+    assert_eq!(
+        error.to_string(), 
+        "3 entries are missing, and taken from fallback locales instead (2 \
+        for \"de-ch\", 1 for \"it\")",
+    );
+    assert!(parsed.warnings[0].warning().starts_with("warning: 3 entries"));
+}
+
+#[test]
+fn fallback_levels() {
+    let source = "
+        !locales en de
+        !fallback de en
+        a:
+            en \"a\"
+    ";
+
+    let errors = Parser::from_text(&format!("!deny fallback{source}"))
+    .parse()
+    .expect_err("should be err");
+    assert!(
+        matches!(errors[..], [Error::Parse(_, ParseError::FallbackUsed(1, _))]),
+        "{errors:?}",
+    );
+
+    let parsed = Parser::from_text(&format!("!deny-release fallback{source}"))
+    .parse()
+    .expect("should be ok");
+    assert!(parsed.warnings.is_empty());
+    assert_eq!(parsed.release_errors.len(), 1);
+
+    // Without a fallback, a missing entry is still an error
+    let errors = Parser::from_text("!locales en de\na:\n en \"a\"")
+    .parse()
+    .expect_err("should be err");
+    assert!(
+        matches!(errors[..], [Error::Parse(_, ParseError::EntryMissingLocale(..))]),
+        "{errors:?}",
+    );
+}
+
+#[test]
+fn bad_fallbacks() {
+    for (line, expected) in [
+        ("!fallback", "missing values for config \"fallback\""),
+        ("!fallback de", "missing values for config \"fallback\""),
+        ("!fallback de fr", "encountered locale \"fr\", but it has not been \
+            declared"),
+        ("!fallback de de", "invalid value \"de\" for config \"fallback\""),
+        ("!fallback de en\n!fallback de en", "config \"fallback\" is given \
+            more than once for locale \"de\""),
+    ] {
+        let source = format!("!locales en de\n{line}\nkey:\n en \"a\"\n de \"b\"");
+        assert_eq!(first_error(&source), expected, "src: {line}");
+    }
+}
+
+#[test]
+fn inherited_entries() {
+    let source = "
+        !locales en en-gb:en en-scot:en-gb fr
+        colour:
+            en \"color\"
+            en-gb \"colour\"
+            fr \"couleur\"
+        hello:
+            en \"hello\"
+            en-scot \"hullo\"
+            fr \"bonjour\"
+    ";
+    let parsed = Parser::from_text(source).parse().expect("should be ok");
+
+    assert!(parsed.warnings.is_empty());
+    assert_eq!(
+        parsed.keys[0].entries, 
+        ["color", "colour", "colour", "couleur"].map(Message::from),
+    );
+    assert_eq!(parsed.keys[0].inherited, vec![(2, 1)]);
+    assert_eq!(parsed.keys[1].inherited, vec![(1, 0)]);
+    assert_eq!(
+        parsed.keys[1].comment.as_deref(), 
+        Some(" # Locale notes\n- *en_gb*: inherited from *en*\n"),
+    );
+
+    // An entry is inherited from the root of the chain
+    let source = "!locales en en-gb:en en-scot:en-gb\nkey:\n en \"a\"";
+    let parsed = Parser::from_text(source).parse().expect("should be ok");
+    assert_eq!(parsed.keys[0].inherited, vec![(1, 0), (2, 0)]);
+}
+
+#[test]
+fn inherited_fallbacks() {
+    let source = "
+        !locales en de de-ch:de fr fr-ch:fr
+        !fallback de en
+        !fallback fr-ch de
+        thanks:
+            en \"thanks\"
+            fr \"merci\"
+    ";
+    let parsed = Parser::from_text(source).parse().expect("should be ok");
+
+    // A regional locale inherits a fallback, but prefers its parent's entry
+    // to its own fallbacks
+    assert_eq!(
+        parsed.keys[0].entries, 
+        ["thanks", "thanks", "thanks", "merci", "merci"].map(Message::from),
+    );
+    assert_eq!(parsed.keys[0].inherited, vec![(2, 1), (4, 3)]);
+
+    let [Error::Parse(_, error)] = parsed.warnings.as_slice() else {
+        panic!("should be one warning, got {:?}", parsed.warnings)
+    };
+    assert!(error.to_string().ends_with("(1 for \"de\")"), "{error}");
+}
+
+#[test]
+fn bad_inheritance() {
+    for (line, expected) in [
+        ("!locales en-gb:en en", "encountered locale \"en\", but it has not \
+            been declared"),
+        ("!locales en en-gb:", "name cannot be empty"),
+    ] {
+
+        assert_eq!(first_error(line), expected, "src: {line}");
+    }
+}
+
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn plural_message() {
+    let line = "{n, plural, =0 {none} one {# {unit}} other {{0} of #}}!";
+    let (arguments, message) = MessageParser::parse(line, Syntax::Format)
+    .unwrap();
+
+    assert_eq!(arguments, vec!["n", "unit", "0"]);
+    assert_eq!(
+        message, 
+        Message(vec![
+            Part::Plural(Plural {
+                argument: String::from("n"),
+                ordinal: false,
+                exact: vec![(0, Message::from("none"))],
+                categories: vec![
+                    (Category::One, Message::from("{n} {unit}")),
+                    (Category::Other, Message::from("{arg0} of {n}")),
+                ],
+            }),
+            Part::Text(String::from("!")),
+        ]),
+    );
+
+    // Branches may hold plurals of their own, where `#` is their count
+    let line = "{a, plural, other {#: {b, plural, other {#}}}}";
+    let (_, message) = MessageParser::parse(line, Syntax::Format)
+    .unwrap();
+    let plurals = message.plurals();
+
+    assert_eq!(plurals.len(), 2);
+    assert_eq!(plurals[1].categories[0].1, Message::from("{b}"));
+    assert_eq!(
+        plurals[0].categories[0].1.0[0], 
+        Part::Text(String::from("{a}: ")),
+    );
+
+    // Literal braces and `#` in a branch are escaped, as the reader leaves them
+    let line = r"{n, plural, other {\{#\} \# \\}}";
+    let (_, message) = MessageParser::parse(line, Syntax::Format)
+    .unwrap();
+    assert_eq!(
+        message.plurals()[0].categories[0].1, 
+        Message::from(r"{{{n}}} # \"),
+    );
+}
+
+#[test]
+fn bad_plural_messages() {
+    for (line, expected) in [
+        ("{n, plural, one {#}}", "\"n\" has no \"other\" branch"),
+        ("{n, plural, other {#} other {#}}", "branch \"other\" is given \
+            more than once"),
+        ("{n, plural, lots {#} other {#}}", "invalid plural selector \"lots\"; \
+            expected \"zero\", \"one\", \"two\", \"few\", \"many\", \
+            \"other\", or an exact count like \"=0\""),
+        ("{n, plural, =x {#} other {#}}", "invalid plural selector \"=x\"; \
+            expected \"zero\", \"one\", \"two\", \"few\", \"many\", \
+            \"other\", or an exact count like \"=0\""),
+        ("{n, plural, other}", "expected a branch such as \"other {...}\", \
+            but found \"other\""),
+        ("{n, plural}", "expected a branch such as \"other {...}\", but \
+            found \"plural\""),
+        ("{n, plural", "expected a branch such as \"other {...}\", but \
+            found \"plural\""),
+        ("{n, plural, other {#}", "value contains nested or an unclosed \
+            opening brace '{'"),
+        ("{n, plural, other {#", "value contains a branch that is not \
+            closed by '}'"),
+        ("{n, choice, other {#}}", "unknown construct \"choice\"; expected \
+            \"plural\", \"select\", or \"selectordinal\""),
+    ] {
+        let error = MessageParser::parse(line, Syntax::Format)
+        .expect_err("should be err");
+        assert_eq!(error.to_string(), expected, "src: {line}");
+    }
+}
+
+#[test]
+fn plural_categories_per_locale() {
+    let source = "
+        !locales en pl
+        files:
+            en \"{n, plural, one {# file} other {# files}}\"
+            pl \"{n, plural, one {# plik} few {# pliki} many {# plików} other {# pliku}}\"
+    ";
+    let parsed = Parser::from_text(source).parse().expect("should be ok");
+    assert_eq!(parsed.keys[0].arguments, vec!["n"]);
+
+    for (entries, expected) in [
+        (
+            "en \"{n, plural, other {#}}\" pl \"{n, plural, one {#} few {#} \
+                many {#} other {#}}\"", 
+            "plural of \"n\" is missing categories [\"one\"], which locale \
+                \"en\" needs",
+        ),
+        (
+            "en \"{n, plural, one {#} other {#}}\" pl \"{n, plural, one {#} \
+                other {#}}\"", 
+            "plural of \"n\" is missing categories [\"few\", \"many\"], \
+                which locale \"pl\" needs",
+        ),
+        (
+            "en \"{n, plural, one {#} two {#} other {#}}\" pl \"{n}\"", 
+            "plural of \"n\" has category \"two\", which locale \"en\" \
+                does not use",
+        ),
+    ] {
+        let source = format!("!locales en pl\nkey: {entries}");
+        assert_eq!(first_error(&source), expected, "src: {entries}");
+    }
+
+    let source = "!locales xx\nkey: xx \"{n, plural, other {#}}\"";
+    let errors = Parser::from_text(source).parse().expect_err("should be err");
+    assert!(
+        matches!(
+            &errors[..], 
+            [Error::Parse(_, ParseError::PluralUnknownLocale(l))] if l == "xx",
+        ),
+        "{errors:?}",
+    );
+}
+
+#[test]
+fn fallback_plurals() {
+    let source = "
+        !locales en pl pl-pl:pl
+        !fallback pl en
+        files:
+            en \"{n, plural, one {# file} other {# files}}\"
+    ";
+
+    // The entry is checked by the rules of the locale it was written for
+    let parsed = Parser::from_text(source).parse().expect("should be ok");
+    assert_eq!(parsed.keys[0].fallbacks, vec![(1, 0)]);
+    assert_eq!(parsed.keys[0].inherited, vec![(2, 1)]);
+
+    let source = "
+        !locales en pl
+        !fallback en pl
+        files:
+            pl \"{n, plural, one {#} other {#}}\"
+    ";
+    assert_eq!(
+        first_error(source), 
+        "plural of \"n\" is missing categories [\"few\", \"many\"], which \
+            locale \"pl\" needs",
+    );
+}
+
+#[test]
+fn ordinal_categories_per_locale() {
+    let source = "
+        !locales en fr
+        place:
+            en \"{n, selectordinal, one {#st} two {#nd} few {#rd} other {#th}}\"
+            fr \"{n, selectordinal, one {#er} other {#e}}\"
+    ";
+    let parsed = Parser::from_text(source).parse().expect("should be ok");
+    let plurals = parsed.keys[0].entries[0].plurals();
+
+    assert!(plurals[0].ordinal);
+    assert_eq!(plurals[0].categories[3].1, Message::from("{n}th"));
+
+    for (entries, expected) in [
+        (
+            "en \"{n, selectordinal, one {#} other {#}}\" fr \"{n}\"", 
+            "plural of \"n\" is missing categories [\"two\", \"few\"], \
+                which locale \"en\" needs",
+        ),
+        (
+            "en \"{n}\" fr \"{n, selectordinal, one {#} few {#} other {#}}\"", 
+            "plural of \"n\" has category \"few\", which locale \"fr\" \
+                does not use",
+        ),
+        (
+            "en \"{n}\" fr \"{n, plural, one {#} other {#}}, \
+                {n, selectordinal, other {#}}\"", 
+            "plural of \"n\" is missing categories [\"one\"], which locale \
+                \"fr\" needs",
+        ),
+    ] {
+        let source = format!("!locales en fr\nkey: {entries}");
+        assert_eq!(first_error(&source), expected, "src: {entries}");
+    }
+
+    // Known cardinals do not make known ordinals
+    let source = "!locales mk\nkey: mk \"{n, selectordinal, other {#}}\"";
+    let errors = Parser::from_text(source).parse().expect_err("should be err");
+    assert!(
+        matches!(
+            &errors[..], 
+            [Error::Parse(_, ParseError::OrdinalUnknownLocale(l))] if l == "mk",
+        ),
+        "{errors:?}",
+    );
+
+    let (_, message) = MessageParser::parse(
+        "{ n, selectordinal, one {#'{'st'}'} other {#th} }", 
+        Syntax::Icu,
+    ).unwrap();
+    assert!(message.plurals()[0].ordinal);
+    assert_eq!(message.plurals()[0].categories[0].1, Message::from("{n}{{st}}"));
+}
+
+#[test]
+fn select_message() {
+    let line = "{g, select, female {She} other {They}} has {n, plural, \
+        one {{g, select, female {her} other {their}} # cat} other {# cats}}";
+    let (arguments, message) = MessageParser::parse(line, Syntax::Format)
+    .unwrap();
+
+    assert_eq!(arguments, vec!["g", "n"]);
+    assert_eq!(
+        message.0[0], 
+        Part::Select(Select {
+            argument: String::from("g"),
+            branches: vec![
+                (String::from("female"), Message::from("She")),
+                (String::from("other"), Message::from("They")),
+            ],
+        }),
+    );
+
+    // A select in a plural keeps its count
+    let selects = message.selects();
+    assert_eq!(selects.len(), 2);
+    assert_eq!(message.plurals()[0].categories[0].1.0[1], Part::Text(
+        String::from(" {n} cat"),
+    ));
+    assert_eq!(message.displayed(), vec!["n", "n"]);
+}
+
+#[test]
+fn bad_select_messages() {
+    for (line, expected) in [
+        ("{g, select, female {She}}", "\"g\" has no \"other\" branch"),
+        ("{g, select, he {He} he {He} other {They}}", "branch \"he\" is given \
+            more than once"),
+        ("{g, select, non.binary {They} other {They}}", "select branch \
+            \"non.binary\" contains invalid char \".\", but must be only \
+            alphanumeric, '-', or '_'"),
+        ("{g, select, {They}}", "expected a branch such as \"other {...}\", \
+            but found \"\""),
+        ("{g, choose, other {#}}", "unknown construct \"choose\"; expected \
+            \"plural\", \"select\", or \"selectordinal\""),
+    ] {
+        let error = MessageParser::parse(line, Syntax::Format)
+        .expect_err("should be err");
+        assert_eq!(error.to_string(), expected, "src: {line}");
+    }
+}
+
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn icu_messages() {
+    for (line, template) in [
+        ("It''s {name}'s", "It's {name}'s"),
+        ("'{'braces'}' and '{name}'", "{{braces}} and {{name}}"),
+        ("'{it''s}'", "{{it's}}"),
+        ("{ name }, { 0 , number }", "{name}, {arg0}"),
+        ("50% '#1", "50% '#1"),
+        (r"\\ and \#", r"\ and #"),
+        ("'{unclosed", "{{unclosed"),
+    ] {
+        let (_, message) = MessageParser::parse(line, Syntax::Icu)
+        .unwrap();
+        assert_eq!(message, Message::from(template), "src: {line}");
+    }
+
+    let line = "{ n , plural , =0 {none} other {'#'# of '{'#'}'}}";
+    let (arguments, message) = MessageParser::parse(line, Syntax::Icu)
+    .unwrap();
+
+    assert_eq!(arguments, vec!["n"]);
+    assert_eq!(
+        message.plurals()[0].categories[0].1, 
+        Message::from("#{n} of {{{n}}}"),
+    );
+
+    for (line, expected) in [
+        ("{a:>3}", "line \"{a:>3}\" contains argument \"a\" with invalid char \
+            \":\", but must be only alphanumeric, '-', or '_'"),
+        ("{{a}}", "line \"{{a}}\" contains argument \"\" with invalid char \
+            \"{\", but must be only alphanumeric, '-', or '_'"),
+        ("{d, date, short}", "\"date\" is not supported in ICU syntax"),
+        ("{n, ordinal}", "\"ordinal\" is not supported in ICU syntax"),
+        ("{n, plural, offset:1 other {#}}", "\"offset\" is not supported in \
+            ICU syntax"),
+        ("{n, plural, other {'}}", "value contains a branch that is not \
+            closed by '}'"),
+        ("{n, plural", "expected a branch such as \"other {...}\", but \
+            found \"plural\""),
+        (r"\{a\}", "braces are quoted as '{' and '}' in ICU syntax, rather \
+            than escaped with a backslash"),
+        (r"{n, plural, other {\}}}", "braces are quoted as '{' and '}' in \
+            ICU syntax, rather than escaped with a backslash"),
+    ] {
+        let error = MessageParser::parse(line, Syntax::Icu)
+        .expect_err("should be err");
+        assert_eq!(error.to_string(), expected, "src: {line}");
+    }
+}
+
+#[test]
+fn syntax_config() {
+    let source = "
+        !locales en
+        a: en \"'{a}'\"
+        !syntax icu
+        b: en \"'{b}'\"
+        !syntax format
+        c: en \"'{c}'\"
+    ";
+    let keys = Parser::from_text(source).parse().unwrap().keys;
+
+    assert_eq!(keys[0].entries[0], Message::from("'{a}'"));
+    assert_eq!(keys[1].entries[0], Message::from("{{b}}"));
+    assert_eq!(keys[2].entries[0], Message::from("'{c}'"));
+    assert_eq!(keys[1].arguments, Vec::<String>::new());
+
+    for (line, expected) in [
+        ("!syntax", "missing values for config \"syntax\""),
+        ("!syntax icu format", "invalid value \"icu format\" for config \
+            \"syntax\""),
+        ("!syntax fluent", "invalid value \"fluent\" for config \"syntax\""),
+    ] {
+
+        assert_eq!(first_error(line), expected, "src: {line}");
+    }
+}
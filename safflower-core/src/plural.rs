@@ -0,0 +1,420 @@
+use proc_macro2::TokenStream;
+
+#[cfg(test)]
+mod tests;
+
+/// A CLDR plural category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Category {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+impl Category {
+    /// Every category, in order.
+    pub const ALL: [Self; 6] = [
+        Self::Zero,
+        Self::One,
+        Self::Two,
+        Self::Few,
+        Self::Many,
+        Self::Other,
+    ];
+
+    /// Reads a category as written in a value, e.g. `one`.
+    #[must_use]
+    pub fn parse(category: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == category)
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Zero => "zero",
+            Self::One => "one",
+            Self::Two => "two",
+            Self::Few => "few",
+            Self::Many => "many",
+            Self::Other => "other",
+        }
+    }
+}
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Declares the plural rules, both cardinal and ordinal. Each is written once,
+/// as the body of a function of an integer `n`, which is used both to validate
+/// values and as the code generated to pick a category.
+///
+/// The categories listed are all those the rule has in CLDR. A value must
+/// give every one of them, unless the ones it must give are listed after
+/// them; this is for categories only decimals fall into, and for those
+/// whose counts read fine with `other` (as do millions in French).
+macro_rules! rules {
+    ($(
+        $(#[$meta:meta])*
+        $rule:ident [$($category:ident),+] $(=> [$($required:ident),+])?
+            |$n:ident| $body:expr;
+    )+) => {
+        /// A plural rule, picking the category of a count.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Rule {
+            $($(#[$meta])* $rule,)+
+        }
+        impl Rule {
+            /// Every category of the rule, including those only used for
+            /// decimals.
+            #[must_use]
+            pub const fn categories(self) -> &'static [Category] {
+                match self {
+                    $(Self::$rule => &[$(Category::$category),+],)+
+                }
+            }
+
+            /// The categories a value must give. Any others fall back to
+            /// `other`, which is always among them.
+            #[must_use]
+            pub const fn required(self) -> &'static [Category] {
+                match self {
+                    $(Self::$rule => rules!(
+                        @required [$($category),+] $([$($required),+])?
+                    ),)+
+                }
+            }
+
+            /// Picks the category of a count.
+            #[must_use]
+            pub fn select(self, n: u64) -> Category {
+                match self {
+                    $(Self::$rule => { let $n = n; $body },)+
+                }
+            }
+
+            /// The name of the rule.
+            #[must_use]
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(Self::$rule => stringify!($rule),)+
+                }
+            }
+
+            /// Gives the name of the count and the code picking its category,
+            /// which refers to a `Category` enum like this one.
+            ///
+            /// # Panics
+            /// Never, as the code is valid Rust.
+            #[must_use]
+            pub fn tokens(self) -> (TokenStream, TokenStream) {
+                let (n, body) = match self {
+                    $(Self::$rule => (stringify!($n), stringify!($body)),)+
+                };
+
+                (
+                    n.parse().expect("the name should be valid"),
+                    body.parse().expect("the rule should be valid"),
+                )
+            }
+        }
+    };
+
+    (@required [$($category:ident),+]) => { &[$(Category::$category),+] };
+    (@required [$($category:ident),+] [$($required:ident),+]) => {
+        &[$(Category::$required),+]
+    };
+}
+
+rules! {
+    /// Japanese, Chinese, Korean, and others without plural forms.
+    None [Other] |_n| Category::Other;
+
+    /// English, German, and most others, where only 1 is singular.
+    OneOther [One, Other]
+        |n| if n == 1 { Category::One } else { Category::Other };
+
+    /// Hindi, Persian, and others, where 0 is singular as well.
+    ZeroOneOther [One, Other]
+        |n| if n <= 1 { Category::One } else { Category::Other };
+
+    /// French and Brazilian Portuguese: 0 and 1 are singular, and millions
+    /// have a form of their own.
+    French [One, Many, Other] => [One, Other] |n| match n {
+        0 | 1 => Category::One,
+        n if n % 1_000_000 == 0 => Category::Many,
+        _ => Category::Other,
+    };
+
+    /// Spanish, Italian, Catalan, and European Portuguese: 1 is singular,
+    /// and millions have a form of their own.
+    Romance [One, Many, Other] => [One, Other] |n| match n {
+        1 => Category::One,
+        n if n != 0 && n % 1_000_000 == 0 => Category::Many,
+        _ => Category::Other,
+    };
+
+    /// Russian, Ukrainian, and Belarusian.
+    EastSlavic [One, Few, Many, Other] |n| match (n % 10, n % 100) {
+            (1, r) if r != 11 => Category::One,
+            (2..=4, r) if !(12..=14).contains(&r) => Category::Few,
+            _ => Category::Many,
+        };
+
+    /// Polish.
+    Polish [One, Few, Many, Other] |n| match (n, n % 10, n % 100) {
+            (1, _, _) => Category::One,
+            (_, 2..=4, r) if !(12..=14).contains(&r) => Category::Few,
+            _ => Category::Many,
+        };
+
+    /// Czech and Slovak, where `many` is only for decimals.
+    Czech [One, Few, Many, Other] => [One, Few, Other] |n| match n {
+        1 => Category::One,
+        2..=4 => Category::Few,
+        _ => Category::Other,
+    };
+
+    /// Croatian, Serbian, and Bosnian.
+    Balkan [One, Few, Other] |n| match (n % 10, n % 100) {
+        (1, r) if r != 11 => Category::One,
+        (2..=4, r) if !(12..=14).contains(&r) => Category::Few,
+        _ => Category::Other,
+    };
+
+    /// Lithuanian, where `many` is only for decimals.
+    Lithuanian [One, Few, Many, Other] => [One, Few, Other]
+        |n| match (n % 10, n % 100) {
+            (_, 11..=19) => Category::Other,
+            (1, _) => Category::One,
+            (2..=9, _) => Category::Few,
+            _ => Category::Other,
+        };
+
+    /// Latvian.
+    Latvian [Zero, One, Other] |n| match (n % 10, n % 100) {
+        (0, _) | (_, 11..=19) => Category::Zero,
+        (1, _) => Category::One,
+        _ => Category::Other,
+    };
+
+    /// Romanian.
+    Romanian [One, Few, Other] |n| match (n, n % 100) {
+        (1, _) => Category::One,
+        (0, _) | (_, 1..=19) => Category::Few,
+        _ => Category::Other,
+    };
+
+    /// Slovenian.
+    Slovenian [One, Two, Few, Other] |n| match n % 100 {
+        1 => Category::One,
+        2 => Category::Two,
+        3 | 4 => Category::Few,
+        _ => Category::Other,
+    };
+
+    /// Arabic.
+    Arabic [Zero, One, Two, Few, Many, Other] |n| match (n, n % 100) {
+        (0, _) => Category::Zero,
+        (1, _) => Category::One,
+        (2, _) => Category::Two,
+        (_, 3..=10) => Category::Few,
+        (_, 11..=99) => Category::Many,
+        _ => Category::Other,
+    };
+
+    /// Hebrew.
+    Hebrew [One, Two, Other] |n| match n {
+        1 => Category::One,
+        2 => Category::Two,
+        _ => Category::Other,
+    };
+
+    /// Irish.
+    Irish [One, Two, Few, Many, Other] |n| match n {
+        1 => Category::One,
+        2 => Category::Two,
+        3..=6 => Category::Few,
+        7..=10 => Category::Many,
+        _ => Category::Other,
+    };
+
+    /// Welsh.
+    Welsh [Zero, One, Two, Few, Many, Other] |n| match n {
+        0 => Category::Zero,
+        1 => Category::One,
+        2 => Category::Two,
+        3 => Category::Few,
+        6 => Category::Many,
+        _ => Category::Other,
+    };
+
+    /// Scottish Gaelic.
+    Gaelic [One, Two, Few, Other] |n| match n {
+        1 | 11 => Category::One,
+        2 | 12 => Category::Two,
+        3..=10 | 13..=19 => Category::Few,
+        _ => Category::Other,
+    };
+
+    /// Icelandic and Macedonian, where numbers ending in 1 are singular.
+    EndsInOne [One, Other] |n| match (n % 10, n % 100) {
+        (1, r) if r != 11 => Category::One,
+        _ => Category::Other,
+    };
+
+    /// Filipino.
+    Filipino [One, Other] |n| match n % 10 {
+        4 | 6 | 9 if n > 3 => Category::Other,
+        _ => Category::One,
+    };
+
+    /// English ordinals: 1st, 2nd, 3rd, 4th, 11th, 21st.
+    EnglishOrdinal [One, Two, Few, Other] |n| match (n % 10, n % 100) {
+        (1, r) if r != 11 => Category::One,
+        (2, r) if r != 12 => Category::Two,
+        (3, r) if r != 13 => Category::Few,
+        _ => Category::Other,
+    };
+
+    /// French and others where only the first has an ordinal of its own:
+    /// 1er, 2e.
+    FirstOrdinal [One, Other]
+        |n| if n == 1 { Category::One } else { Category::Other };
+
+    /// Italian ordinals, where e.g. 8 and 11 take an elided article.
+    ItalianOrdinal [Many, Other] |n| match n {
+        8 | 11 | 80 | 800 => Category::Many,
+        _ => Category::Other,
+    };
+
+    /// Catalan ordinals: 1r, 2n, 3r, 4t, 5è.
+    CatalanOrdinal [One, Two, Few, Other] |n| match n {
+        1 | 3 => Category::One,
+        2 => Category::Two,
+        4 => Category::Few,
+        _ => Category::Other,
+    };
+
+    /// Swedish ordinals: 1:a, 2:a, 3:e.
+    SwedishOrdinal [One, Other] |n| match (n % 10, n % 100) {
+        (1 | 2, r) if r != 11 && r != 12 => Category::One,
+        _ => Category::Other,
+    };
+
+    /// Hungarian ordinals, by the vowel of the number.
+    HungarianOrdinal [One, Other] |n| match n {
+        1 | 5 => Category::One,
+        _ => Category::Other,
+    };
+
+    /// Welsh ordinals.
+    WelshOrdinal [Zero, One, Two, Few, Many, Other] |n| match n {
+        0 | 7..=9 => Category::Zero,
+        1 => Category::One,
+        2 => Category::Two,
+        3 | 4 => Category::Few,
+        5 | 6 => Category::Many,
+        _ => Category::Other,
+    };
+
+    /// Ukrainian ordinals, where those ending in 3 but not 13 differ.
+    UkrainianOrdinal [Few, Other] |n| match (n % 10, n % 100) {
+        (3, r) if r != 13 => Category::Few,
+        _ => Category::Other,
+    };
+
+    /// Hindi and Gujarati ordinals.
+    HindiOrdinal [One, Two, Few, Many, Other] |n| match n {
+        1 => Category::One,
+        2 | 3 => Category::Two,
+        4 => Category::Few,
+        6 => Category::Many,
+        _ => Category::Other,
+    };
+}
+
+/// Finds the cardinal plural rule of a locale, by its language, unless the
+/// locale has a rule of its own (e.g. `pt-pt`). Case does not matter, nor
+/// does `-` versus `_`.
+#[must_use]
+pub fn cardinal(locale: &str) -> Option<Rule> {
+    let locale = locale.to_ascii_lowercase().replace('-', "_");
+
+    if locale == "pt_pt" { return Some(Rule::Romance); }
+
+    let language = locale.split('_').next().unwrap_or_default();
+    let rule = match language {
+        "bo" | "dz" | "id" | "ig" | "ja" | "jv" | "km" | "ko" | "lo" | "ms"
+        | "my" | "sg" | "th" | "to" | "vi" | "wo" | "yo" | "yue" | "zh"
+            => Rule::None,
+
+        "af" | "az" | "bg" | "da" | "de" | "el" | "en" | "eo" | "et" | "eu"
+        | "fi" | "fo" | "fy" | "gl" | "hu" | "ka" | "kk" | "ky" | "lb" | "ml"
+        | "mn" | "mr" | "nb" | "ne" | "nl" | "nn" | "no" | "or" | "ps" | "sq"
+        | "sv" | "sw" | "ta" | "te" | "tk" | "tr" | "ug" | "ur" | "uz"
+            => Rule::OneOther,
+
+        "am" | "as" | "bn" | "fa" | "gu" | "hi" | "hy" | "kn" | "ln" | "pa"
+        | "si" | "ti" | "zu"
+            => Rule::ZeroOneOther,
+
+        "fr" | "pt" => Rule::French,
+        "ca" | "es" | "it" => Rule::Romance,
+        "be" | "ru" | "uk" => Rule::EastSlavic,
+        "pl" => Rule::Polish,
+        "cs" | "sk" => Rule::Czech,
+        "bs" | "hr" | "sh" | "sr" => Rule::Balkan,
+        "lt" => Rule::Lithuanian,
+        "lv" => Rule::Latvian,
+        "mo" | "ro" => Rule::Romanian,
+        "sl" => Rule::Slovenian,
+        "ar" => Rule::Arabic,
+        "he" | "iw" => Rule::Hebrew,
+        "ga" => Rule::Irish,
+        "cy" => Rule::Welsh,
+        "gd" => Rule::Gaelic,
+        "is" | "mk" => Rule::EndsInOne,
+        "fil" | "tl" => Rule::Filipino,
+
+        _ => return None,
+    };
+
+    Some(rule)
+}
+
+/// Finds the ordinal plural rule of a locale, by its language, like
+/// [`cardinal`].
+#[must_use]
+pub fn ordinal(locale: &str) -> Option<Rule> {
+    let locale = locale.to_ascii_lowercase().replace('-', "_");
+
+    let language = locale.split('_').next().unwrap_or_default();
+    let rule = match language {
+        "af" | "am" | "ar" | "bg" | "bs" | "cs" | "da" | "de" | "el" | "es"
+        | "et" | "eu" | "fa" | "fi" | "fy" | "gl" | "he" | "hr" | "id" | "is"
+        | "iw" | "ja" | "km" | "kn" | "ko" | "ky" | "lt" | "lv" | "ml" | "mn"
+        | "my" | "nb" | "nl" | "nn" | "no" | "pa" | "pl" | "ps" | "pt" | "ru"
+        | "sh" | "si" | "sk" | "sl" | "sr" | "sw" | "ta" | "te" | "th" | "tr"
+        | "ur" | "uz" | "yue" | "zh" | "zu"
+            => Rule::None,
+
+        "en" => Rule::EnglishOrdinal,
+        "fil" | "fr" | "ga" | "hy" | "lo" | "mo" | "ms" | "ro" | "tl" | "vi"
+            => Rule::FirstOrdinal,
+        "it" => Rule::ItalianOrdinal,
+        "ca" => Rule::CatalanOrdinal,
+        "sv" => Rule::SwedishOrdinal,
+        "hu" => Rule::HungarianOrdinal,
+        "cy" => Rule::WelshOrdinal,
+        "gu" | "hi" => Rule::HindiOrdinal,
+        "uk" => Rule::UkrainianOrdinal,
+
+        _ => return None,
+    };
+
+    Some(rule)
+}
@@ -0,0 +1,171 @@
+use super::*;
+
+/// Every rule, by a language that uses it.
+fn rules() -> Vec<Rule> {
+    [
+        "ja", "en", "hi", "fr", "es", "ru", "pl", "cs", "hr", "lt", "lv", 
+        "ro", "sl", "ar", "he", "ga", "cy", "gd", "is", "fil",
+    ]
+    .into_iter()
+    .map(|l| cardinal(l).unwrap())
+    .chain(
+        ["de", "en", "fr", "it", "ca", "sv", "hu", "cy", "hi", "uk"]
+        .into_iter()
+        .map(|l| ordinal(l).unwrap())
+    )
+    .collect()
+}
+
+#[test]
+fn required_categories() {
+    let counts = (0..10_000).chain([1_000_000, 2_000_000, 1_000_001]);
+
+    for rule in rules() {
+        let name = rule.name();
+        let reached = counts
+        .clone()
+        .map(|n| rule.select(n))
+        .collect::<Vec<_>>();
+        let required = rule.required();
+
+        assert!(required.contains(&Category::Other), "rule: {name}");
+        assert!(
+            required.iter().all(|c| rule.categories().contains(c)), 
+            "rule: {name}",
+        );
+        assert!(
+            reached.iter().all(|c| rule.categories().contains(c)), 
+            "rule: {name}",
+        );
+
+        // Every required category is reached, apart from `other`
+        for category in required {
+            assert!(
+                *category == Category::Other || reached.contains(category),
+                "rule: {name}, category: {category}",
+            );
+        }
+
+        // Categories left out fall back to `other`, so it must be a form 
+        // for whole numbers as well
+        if reached.iter().any(|c| !required.contains(c)) {
+            assert!(reached.contains(&Category::Other), "rule: {name}");
+        }
+    }
+}
+
+#[test]
+fn cardinal_categories() {
+    use Category::*;
+
+    for (locale, categories) in [
+        ("en", [Other, One, Other, Other]),
+        ("fr", [One, One, Other, Other]),
+        ("ru", [Many, One, Few, Many]),
+        ("ar", [Zero, One, Two, Many]),
+        ("zh", [Other, Other, Other, Other]),
+    ] {
+        let rule = cardinal(locale).unwrap();
+        let actual = [0, 1, 2, 11].map(|n| rule.select(n));
+        assert_eq!(actual, categories, "locale: {locale}");
+    }
+
+    let pl = cardinal("pl").unwrap();
+    assert_eq!(pl.select(22), Few);
+    assert_eq!(pl.select(12), Many);
+    assert_eq!(cardinal("fr").unwrap().select(3_000_000), Many);
+}
+
+#[test]
+fn cardinal_locales() {
+    assert_eq!(cardinal("en-GB"), Some(Rule::OneOther));
+    assert_eq!(cardinal("pt_BR"), Some(Rule::French));
+    assert_eq!(cardinal("pt-PT"), Some(Rule::Romance));
+    assert_eq!(cardinal("xx"), None);
+}
+
+#[test]
+fn rule_tokens() {
+    let (n, body) = Rule::OneOther.tokens();
+
+    assert_eq!(n.to_string(), "n");
+    assert_eq!(
+        body.to_string(), 
+        "if n == 1 { Category :: One } else { Category :: Other }",
+    );
+}
+
+#[test]
+fn ordinal_categories() {
+    use Category::*;
+
+    // Every language with ordinal rules, and the categories of these numbers
+    let numbers = [0, 1, 2, 3, 4, 5, 6, 8, 11, 13, 23, 101];
+    for (languages, categories) in [
+        (
+            &[
+                "af", "am", "ar", "bg", "bs", "cs", "da", "de", "el", "es", 
+                "et", "eu", "fa", "fi", "fy", "gl", "he", "hr", "id", "is", 
+                "iw", "ja", "km", "kn", "ko", "ky", "lt", "lv", "ml", "mn", 
+                "my", "nb", "nl", "nn", "no", "pa", "pl", "ps", "pt", "ru", 
+                "sh", "si", "sk", "sl", "sr", "sw", "ta", "te", "th", "tr", 
+                "ur", "uz", "yue", "zh", "zu",
+            ][..],
+            [Other; 12],
+        ),
+        (
+            &["en"],
+            [Other, One, Two, Few, Other, Other, Other, Other, Other, Other, 
+                Few, One],
+        ),
+        (
+            &["fil", "fr", "ga", "hy", "lo", "mo", "ms", "ro", "tl", "vi"],
+            [Other, One, Other, Other, Other, Other, Other, Other, Other, 
+                Other, Other, Other],
+        ),
+        (
+            &["it"],
+            [Other, Other, Other, Other, Other, Other, Other, Many, Many, 
+                Other, Other, Other],
+        ),
+        (
+            &["ca"],
+            [Other, One, Two, One, Few, Other, Other, Other, Other, Other, 
+                Other, Other],
+        ),
+        (
+            &["sv"],
+            [Other, One, One, Other, Other, Other, Other, Other, Other, Other, 
+                Other, One],
+        ),
+        (
+            &["hu"],
+            [Other, One, Other, Other, Other, One, Other, Other, Other, Other, 
+                Other, Other],
+        ),
+        (
+            &["cy"],
+            [Zero, One, Two, Few, Few, Many, Many, Zero, Other, Other, Other, 
+                Other],
+        ),
+        (
+            &["gu", "hi"],
+            [Other, One, Two, Two, Few, Other, Many, Other, Other, Other, 
+                Other, Other],
+        ),
+        (
+            &["uk"],
+            [Other, Other, Other, Few, Other, Other, Other, Other, Other, 
+                Other, Few, Other],
+        ),
+    ] {
+        for locale in languages {
+            let rule = ordinal(locale).unwrap();
+            let actual = numbers.map(|n| rule.select(n));
+            assert_eq!(actual, categories, "locale: {locale}");
+        }
+    }
+
+    assert_eq!(ordinal("en-GB"), Some(Rule::EnglishOrdinal));
+    assert_eq!(ordinal("xx"), None);
+}
@@ -6,24 +6,47 @@ pub use error::ReadError;
 #[cfg(test)]
 mod tests;
 
+/// A token or an error, and where in the source it was found.
+pub type Read = Result<(Position, Token), (Position, ReadError)>;
+
 #[derive(Clone)]
 pub struct CharReader {
     chars: Vec<char>,
-    buffer: Option<char>,
+    /// Where the next char will be read from.
+    position: Position,
+    /// Where the last char was read from.
+    last: Position,
 }
 impl CharReader {
     #[must_use]
     pub fn new(source: &str) -> Self {
         Self {
             chars: source.chars().rev().collect(),
-            buffer: None,
+            position: Position::default(),
+            last: Position::default(),
+        }
+    }
+
+    /// Takes the next char, keeping track of the position.
+    fn pop(&mut self) -> Option<char> {
+        let c = self.chars.pop()?;
+        self.last = self.position;
+        self.position.advance(c);
+        Some(c)
+    }
+
+    fn peek(&self) -> Option<char> { self.chars.last().copied() }
+
+    fn skip_line(&mut self) {
+        while let Some(c) = self.pop() {
+            if c == '\n' { break; }
         }
     }
 
     fn read_comment(&mut self) -> Token {
         let mut comment = String::new();
 
-        while let Some(c) = self.chars.pop() {
+        while let Some(c) = self.pop() {
             if c == '\n' { break; }
             comment += &c.to_string();
         }
@@ -35,7 +58,7 @@ impl CharReader {
         let mut line = String::new();
         let mut add = true;
 
-        while let Some(c) = self.chars.pop() {
+        while let Some(c) = self.pop() {
             if c == '\n' { break; }
             if c == '#' { add = false; }
  
@@ -44,99 +67,295 @@ impl CharReader {
         Token::Config(line)
     }
     
-    fn read_value(&mut self) -> Result<Token, ReadError> {
+    fn read_value(
+        &mut self, 
+        start: Position,
+    ) -> Result<Token, (Position, ReadError)> {
         let mut value = String::new();
+        // A bad escape is only reported once the whole value is read, so 
+        // that reading can pick up after it
+        let mut error = None;
+
         loop {
-            match self.chars.pop() {
-                Some('"') => if value.ends_with('\\') {
-                    // Quote may be escaped...
-                    _ = value.pop();
-                    value += "\"";
-                } 
-                else {
-                    // ..or end the value
-                    return Ok(Token::Value(value))
+            match self.pop() {
+                Some('"') => break,
+
+                Some('\\') => {
+                    let position = self.last;
+                    if let Err(e) = self.read_escape(&mut value) {
+                        error.get_or_insert((position, e));
+                    }
                 },
 
                 // Any random char is added to the buffer, unchecked.
-                Some(c) => value += &c.to_string(),
+                Some(c) => value.push(c),
 
                 // If the iterator finished without closing the quote, you have
                 // some problems in your file.
-                None => return Err(ReadError::UnmatchedQuote),
+                None => return Err((start, ReadError::UnmatchedQuote)),
+            }
+        }
+
+        error.map_or(Ok(Token::Value(value)), Err)
+    }
+
+    /// Reads a `"""` block, removing the indentation its lines have in 
+    /// common, along with the first and last lines if they are blank.
+    fn read_block(
+        &mut self, 
+        start: Position,
+    ) -> Result<Token, (Position, ReadError)> {
+        let mut value = String::new();
+        let mut error = None;
+        // Where each line starts in the value, and how far it is indented
+        let mut lines = vec![(0, 0)];
+        let mut indenting = true;
+
+        loop {
+            match self.pop() {
+                Some('"') if self.chars.ends_with(&['"', '"']) => {
+                    _ = self.pop();
+                    _ = self.pop();
+                    break;
+                },
+
+                Some('\\') => {
+                    indenting = false;
+                    let position = self.last;
+                    if let Err(e) = self.read_escape(&mut value) {
+                        error.get_or_insert((position, e));
+                    }
+                },
+
+                Some('\n') => {
+                    value.push('\n');
+                    lines.push((value.len(), 0));
+                    indenting = true;
+                },
+
+                Some(c) => {
+                    indenting &= c.is_whitespace();
+                    let line = lines.last_mut().filter(|_| indenting);
+                    if let Some((_, indent)) = line { 
+                        *indent += c.len_utf8(); 
+                    }
+                    value.push(c);
+                },
+
+                None => return Err((start, ReadError::UnmatchedQuote)),
             }
         }
+
+        error.map_or_else(|| Ok(Token::Value(dedent(&value, &lines))), Err)
+    }
+
+    /// Reads what follows a backslash, and adds the char it stands for.
+    fn read_escape(&mut self, value: &mut String) -> Result<(), ReadError> {
+        let c = match self.pop() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some(c @ ('\\' | '"' | '\'' | '{' | '}' | '#')) => c,
+            Some('u') => self.read_unicode()?,
+
+            // A line may be continued, skipping the indentation of the next,
+            // also when it ends in `\r\n`
+            Some(c) if c == '\n' || c == '\r' && self.peek() == Some('\n') => {
+                while self.peek().is_some_and(char::is_whitespace) {
+                    _ = self.pop();
+                }
+                return Ok(());
+            },
+
+            Some(c) => return Err(ReadError::UnknownEscape(c)),
+            None => return Err(ReadError::UnmatchedQuote),
+        };
+
+        // These mean something to the message parser, so they are left 
+        // escaped for it to read as literals
+        if matches!(c, '\\' | '{' | '}' | '#') { value.push('\\'); }
+        value.push(c);
+
+        Ok(())
+    }
+
+    /// Reads the `{XXXX}` part of a `\u{XXXX}` escape.
+    fn read_unicode(&mut self) -> Result<char, ReadError> {
+        if self.peek() != Some('{') {
+            return Err(ReadError::InvalidUnicodeEscape);
+        }
+        _ = self.pop();
+
+        let mut digits = String::new();
+        while let Some(c) = self.peek().filter(char::is_ascii_hexdigit) {
+            digits.push(c);
+            _ = self.pop();
+        }
+
+        if self.peek() != Some('}') || digits.is_empty() || digits.len() > 6 {
+            return Err(ReadError::InvalidUnicodeEscape);
+        }
+        _ = self.pop();
+
+        u32::from_str_radix(&digits, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or(ReadError::InvalidUnicodeEscape)
     }
     
     fn read_param(&mut self, first: char) -> Result<Token, ReadError> {
         let mut name = Name::new(first)?;
 
         // First we get the token
-        while let Some(char) = self.chars.pop() {
-            match char {
+        while let Some(c) = self.peek() {
+            // The next thing is a quote, so we have a locale
+            if c == '"' { return Ok(Token::Locale(name)); }
+            _ = self.pop();
+
+            match c {
                 c if c.is_whitespace() => break,
 
                 // The next thing is a delimiter, so we have a key
                 ':' => return Ok(Token::Key(name)),
 
-                // The next thing is a quote, so we have a locale
-                '\"' => {
-                    self.buffer = Some('\"');
-                    return Ok(Token::Locale(name));
-                },
-
                 // Any valid char is added to the buffer, unchecked.
                 c => name.add(c)?,
             }
         }
 
         // If we got here, there was a whitespace
-        loop {
-            match self.chars.pop() {
+        while let Some(c) = self.peek() {
+            // The next thing is a quote, so we have a locale
+            if c == '"' { return Ok(Token::Locale(name)); }
+            _ = self.pop();
+
+            match c {
                 // Eat the space
-                Some(c) if c.is_whitespace() => {},
+                c if c.is_whitespace() => {},
 
                 // The next thing is a delimiter, so we have a key
-                Some(':') => return Ok(Token::Key(name)),
-
-                // The next thing is a quote, so we have a locale
-                Some('\"') => {
-                    self.buffer = Some('\"');
-                    return Ok(Token::Locale(name));
-                },
+                ':' => return Ok(Token::Key(name)),
 
                 // Other chars are suspicious
-                Some(c) => return Err(ReadError::InvalidChar(c)),
-
-                None => return Err(ReadError::EOF),
+                c => return Err(ReadError::InvalidChar(c)),
             }
         }
+
+        Err(ReadError::EOF)
     }
 }
 impl Iterator for CharReader {
-    type Item = Result<Token, ReadError>;
+    type Item = Read;
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            let c = match self.buffer.take() {
-                Some(c) => Some(c),
-                None => self.chars.pop(),
-            };
+            let start = self.position;
 
-            return match c? {
-                '#' => Some(Ok(self.read_comment())),
-                '!' => Some(Ok(self.read_config())),
-                '"' => Some(self.read_value()),
+            let result = match self.pop()? {
+                '#' => Ok(self.read_comment()),
+                '!' => Ok(self.read_config()),
+
+                '"' if self.chars.ends_with(&['"', '"']) => {
+                    _ = self.pop();
+                    _ = self.pop();
+                    self.read_block(start)
+                },
+                '"' => self.read_value(start),
 
                 c if c.is_whitespace() => continue,
 
-                c if Name::is_valid(c) => Some(self.read_param(c)),
+                c if Name::is_valid(c) => self
+                    .read_param(c)
+                    .map_err(|e| (self.last, e)),
 
-                c => Some(Err(ReadError::InvalidChar(c))),
-            }
+                c => Err((start, ReadError::InvalidChar(c))),
+            };
+
+            // Whatever is left of a broken line is unlikely to make sense
+            if result.is_err() { self.skip_line(); }
+
+            return Some(result.map(|token| (start, token)));
+        }
+    }
+}
+
+/// Removes the common indentation of the lines of a block, not counting the 
+/// first line (which follows the opening quotes) or blank lines. Only the 
+/// whitespace every line starts with is common, so a tab does not count as a
+/// space. Blank first and last lines are removed entirely.
+fn dedent(value: &str, lines: &[(usize, usize)]) -> String {
+    let mut lines = lines
+    .iter()
+    .enumerate()
+    .map(|(i, &(start, indent))| {
+        let end = lines.get(i + 1).map_or(value.len(), |(next, _)| next - 1);
+        let line = &value[start..end];
+        (line, indent, indent == line.len())
+    })
+    .collect::<Vec<_>>();
+
+    let (first, _, first_blank) = lines.remove(0);
+    if lines.last().is_some_and(|(_, _, blank)| *blank) { _ = lines.pop(); }
+
+    let common = lines
+    .iter()
+    .filter(|(_, _, blank)| !blank)
+    .map(|(line, indent, _)| &line[..*indent])
+    .reduce(common_prefix)
+    .unwrap_or_default();
+
+    // Blank lines may be indented less than the rest
+    let rest = lines
+    .into_iter()
+    .map(|(line, _, _)| &line[common_prefix(common, line).len()..]);
+
+    (!first_blank)
+    .then_some(first)
+    .into_iter()
+    .chain(rest)
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// Gives the longest start `a` and `b` have in common.
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let end = a
+    .char_indices()
+    .zip(b.chars())
+    .find(|((_, x), y)| x != y)
+    .map_or_else(|| a.len().min(b.len()), |((i, _), _)| i);
+
+    &a[..end]
+}
+
+/// A position in a source text.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Position {
+    /// Byte offset from the start of the source.
+    pub offset: usize,
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column number in chars, starting at 1.
+    pub column: usize,
+}
+impl Position {
+    const fn advance(&mut self, c: char) {
+        self.offset += c.len_utf8();
+
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
         }
     }
 }
+impl Default for Position {
+    fn default() -> Self {
+        Self { offset: 0, line: 1, column: 1 }
+    }
+}
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Token {
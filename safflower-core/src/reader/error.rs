@@ -14,4 +14,9 @@ pub enum ReadError {
     UnmatchedQuote,
     #[error("name cannot be empty")]
     EmptyName,
+    #[error("unknown escape sequence '\\{0}'")]
+    UnknownEscape(char),
+    #[error("invalid unicode escape; it must be '\\u{{X}}', where X is 1 \
+        to 6 hex digits that make up a valid char")]
+    InvalidUnicodeEscape,
 }
@@ -1,7 +1,16 @@
 use super::*;
 
 fn read_all(source: &str) -> Result<Vec<Token>, ReadError> { 
-    CharReader::new(source).collect() 
+    CharReader::new(source)
+    .map(|r| r.map(|(_, t)| t).map_err(|(_, e)| e))
+    .collect() 
+}
+
+fn positions(source: &str) -> Vec<(usize, usize)> {
+    CharReader::new(source)
+    .map(|r| r.map_or_else(|(p, _)| p, |(p, _)| p))
+    .map(|p| (p.line, p.column))
+    .collect()
 }
 
 #[test]
@@ -171,3 +180,148 @@ fn multiline() {
         Token::Value(String::from("svenska")),
     ]);
 }
+
+#[test]
+fn token_positions() {
+    for (source, expected) in [
+        ("key: loc \"value\"", vec![(1, 1), (1, 6), (1, 10)]),
+        ("key:\n  loc \"value\"", vec![(1, 1), (2, 3), (2, 7)]),
+        ("# å\nkey:", vec![(1, 1), (2, 1)]),
+        ("\t!locales en\n\nk:", vec![(1, 2), (3, 1)]),
+    ] {
+        assert_eq!(positions(source), expected, "src: '{source}'");
+    }
+}
+
+#[test]
+fn error_positions() {
+    for (source, expected) in [
+        ("key: loc \"value", (1, 10)),
+        ("key: loc\n  $", (2, 3)),
+        ("key\n  loc:", (2, 3)),
+        ("ke$y:", (1, 3)),
+    ] {
+        let error = CharReader::new(source)
+        .find_map(Result::err)
+        .map(|(p, _)| (p.line, p.column));
+
+        assert_eq!(error, Some(expected), "src: '{source}'");
+    }
+}
+
+#[test]
+fn value_escapes() {
+    for (source, value) in [
+        (r#""a\"b""#, "a\"b"),
+        (r#""\n\t\r\0""#, "\n\t\r\0"),
+        (r#""\u{a0}\u{1F600}""#, "\u{a0}\u{1F600}"),
+        // Those meaning something to the message parser stay escaped
+        (r#""a\\""#, r"a\\"),
+        (r#""a\\\"""#, r#"a\\""#),
+        (r#""\{a\} \#""#, r"\{a\} \#"),
+        (r#""\u{7b}""#, r"\{"),
+        ("\"one \\\n    two\"", "one two"),
+        ("\"one \\\r\n    two\"", "one two"),
+    ] {
+        let tokens = read_all(source).unwrap();
+        assert_eq!(
+            tokens, 
+            vec![Token::Value(value.to_string())], 
+            "src: '{source}'"
+        );
+    }
+}
+
+#[test]
+fn bad_escapes() {
+    for (source, column) in [
+        (r#""\q""#, 2),
+        (r#""ab\u{}""#, 4),
+        (r#""\u{110000}""#, 2),
+        (r#""\u{d800}""#, 2),
+        (r#""\u20""#, 2),
+        (r#""\u{1234567}""#, 2),
+        ("\"\\\r two\"", 2),
+    ] {
+        let error = CharReader::new(source)
+        .find_map(Result::err)
+        .map(|(p, _)| p.column);
+
+        assert_eq!(error, Some(column), "src: '{source}'");
+    }
+
+    // Reading continues after the bad value
+    let tokens = CharReader::new("\"\\q\"\nkey:")
+    .map(|r| r.is_ok())
+    .collect::<Vec<_>>();
+    assert_eq!(tokens, vec![false, true]);
+}
+
+#[test]
+fn block() {
+    for (source, value) in [
+        ("\"\"\"\"\"\"", ""),
+        ("\"\"\"abc\"\"\"", "abc"),
+        ("\"\"\"a\"b\"\"c\"\"\"", "a\"b\"\"c"),
+        ("\"\"\"\n    one\n    two\n    \"\"\"", "one\ntwo"),
+        ("\"\"\"\n    one\n\n      two\n  \"\"\"", "one\n\n  two"),
+        ("\"\"\"\n\t\tone\n\t\ttwo\"\"\"", "one\ntwo"),
+        ("\"\"\"first\n    second\n    \"\"\"", "first\nsecond"),
+        ("\"\"\"\n    \\tone\\n\n    two \\\n      three\n\"\"\"", 
+            "\tone\n\ntwo three"),
+        ("\"\"\"\n    an \\\"\"\" inside\n\"\"\"", "an \"\"\" inside"),
+    ] {
+        let tokens = read_all(source).unwrap();
+        assert_eq!(
+            tokens, 
+            vec![Token::Value(value.to_string())], 
+            "src: '{source}'"
+        );
+    }
+}
+
+#[test]
+fn block_mixed_indentation() {
+    // Only indentation that is the same on every line is removed
+    for (source, value) in [
+        ("\"\"\"\n \u{a0}one\n  two\n\"\"\"", "\u{a0}one\n two"),
+        ("\"\"\"\n\u{a0}one\n two\n\"\"\"", "\u{a0}one\n two"),
+        ("\"\"\"\n\u{3000}\u{a0}one\n\u{3000}two\"\"\"", "\u{a0}one\ntwo"),
+        ("\"\"\"\n\tone\n    two\n\"\"\"", "\tone\n    two"),
+        ("\"\"\"\n\u{a0}\u{a0}one\n\u{a0}\n\u{a0}\u{a0}two\"\"\"", 
+            "one\n\ntwo"),
+        ("\"\"\"\n\t one\n\t\n\t two\"\"\"", "one\n\ntwo"),
+    ] {
+        let tokens = read_all(source).unwrap();
+        assert_eq!(
+            tokens, 
+            vec![Token::Value(value.to_string())], 
+            "src: '{source}'"
+        );
+    }
+}
+
+#[test]
+fn block_in_entry() {
+    let source = "
+    key:
+        en \"\"\"
+            First paragraph,
+            still first.
+
+            Second paragraph.
+            \"\"\"
+        it \"uno\"
+    ";
+
+    let tokens = read_all(source).unwrap();
+    assert_eq!(tokens, vec![
+        Token::Key(Name::try_from("key").unwrap()),
+        Token::Locale(Name::try_from("en").unwrap()),
+        Token::Value(String::from(
+            "First paragraph,\nstill first.\n\nSecond paragraph."
+        )),
+        Token::Locale(Name::try_from("it").unwrap()),
+        Token::Value(String::from("uno")),
+    ]);
+}
@@ -8,7 +8,7 @@ mod load;
 mod text;
 
 use load::Loader;
-use text::Texter;
+use text::{Texter, Writer};
 
 #[proc_macro]
 /// Loads a file from the specified path and parses it as a collection of text
@@ -25,6 +25,11 @@ use text::Texter;
 /// The locales come from the config, so e.g. `!locales en es fr` would give 
 /// you three locales, and an enum with the variants `En`, `Es`, and `Fr`, in 
 /// that order.
+/// 
+/// ## Options
+/// The generated module is a private `localisation` by default. Its name and 
+/// visibility may be set after the path, as in 
+/// `load!("ui.txt", mod = ui, vis = pub)`.
 pub fn load(input: TokenStream) -> TokenStream {
     let loader = parse_macro_input!(input as Loader);
     let data = match loader.collect() {
@@ -38,7 +43,76 @@ pub fn load(input: TokenStream) -> TokenStream {
 #[proc_macro]
 /// Acts similarly to `format!`, but takes a key from your previously `load!`ed
 /// file instead of a string literal.
+/// 
+/// The text is in the current locale, unless one is given first, as in 
+/// `text!(in locale, key, args...)`. The key may be given as a path, as in 
+/// `text!(ui::key)`, if it was loaded into a module other than 
+/// `localisation`. Arguments may be named, as in `text!(key, name = value)`.
+/// This also goes for the other text macros.
 pub fn text(input: TokenStream) -> TokenStream {
     let code = parse_macro_input!(input as Texter);
     quote! { #code }.into()
 }
+
+#[proc_macro]
+/// Like `text!`, but instead of formatting a `String`, gives a value that 
+/// formats the text when it is displayed, e.g. by `write!` or `println!`. The
+/// arguments are borrowed, so nothing is allocated.
+pub fn lazy_text(input: TokenStream) -> TokenStream {
+    let code = parse_macro_input!(input as Texter).lazy();
+    quote! { #code }.into()
+}
+
+#[proc_macro]
+/// Acts similarly to `write!`, but takes a key from your previously `load!`ed
+/// file instead of a string literal, writing the text straight into the 
+/// destination.
+pub fn write_text(input: TokenStream) -> TokenStream {
+    let code = parse_macro_input!(input as Writer);
+    quote! { #code }.into()
+}
+
+#[proc_macro]
+/// Acts similarly to `print!`, but takes a key from your previously 
+/// `load!`ed file instead of a string literal.
+pub fn print_text(input: TokenStream) -> TokenStream {
+    output(&quote! { print! }, input)
+}
+
+#[proc_macro]
+/// Acts similarly to `println!`, but takes a key from your previously 
+/// `load!`ed file instead of a string literal.
+pub fn println_text(input: TokenStream) -> TokenStream {
+    output(&quote! { println! }, input)
+}
+
+#[proc_macro]
+/// Acts similarly to `eprint!`, but takes a key from your previously 
+/// `load!`ed file instead of a string literal.
+pub fn eprint_text(input: TokenStream) -> TokenStream {
+    output(&quote! { eprint! }, input)
+}
+
+#[proc_macro]
+/// Acts similarly to `eprintln!`, but takes a key from your previously 
+/// `load!`ed file instead of a string literal.
+pub fn eprintln_text(input: TokenStream) -> TokenStream {
+    output(&quote! { eprintln! }, input)
+}
+
+#[proc_macro]
+/// Acts similarly to `panic!`, but takes a key from your previously 
+/// `load!`ed file instead of a string literal.
+pub fn panic_text(input: TokenStream) -> TokenStream {
+    output(&quote! { panic! }, input)
+}
+
+/// Passes the text to a formatting macro, to be displayed without first 
+/// being formatted into a `String`.
+fn output(
+    macro_name: &proc_macro2::TokenStream, 
+    input: TokenStream,
+) -> TokenStream {
+    let display = parse_macro_input!(input as Texter).display();
+    quote! { #macro_name("{}", #display) }.into()
+}
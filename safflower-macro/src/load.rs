@@ -1,57 +1,227 @@
+use std::path::PathBuf;
+
 use quote::quote;
 use safflower_core::{
     generator::Generator, 
     name::Name, 
-    parser::{Key, ParsedData, Parser}, 
+    parser::{Direction, Key, ParsedData, Parser}, 
 };
 
+/// The most errors reported from a single `load!`. Any more are summarised.
+const MAX_ERRORS: usize = 32;
+
 pub struct Loader {
     span: proc_macro2::Span,
     path: String,
+    module: syn::Ident,
+    visibility: syn::Visibility,
 }
 impl syn::parse::Parse for Loader {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        // Parse pattern:
+        // LIT (, mod = IDENT | , vis = VIS)* ,?
+
         // Get a path
         let path: syn::LitStr = input.parse()?;
 
+        let mut module = None;
+        let mut visibility = None;
+
+        while input.parse::<syn::Token![,]>().is_ok() && !input.is_empty() {
+            if input.peek(syn::Token![mod]) {
+                let token = input.parse::<syn::Token![mod]>()?;
+                _ = input.parse::<syn::Token![=]>()?;
+                set_option(&mut module, input.parse()?, token.span)?;
+                continue;
+            }
+
+            let option: syn::Ident = input.parse()?;
+            _ = input.parse::<syn::Token![=]>()?;
+            match option.to_string().as_str() {
+                "vis" => set_option(
+                    &mut visibility, 
+                    input.parse()?, 
+                    option.span(),
+                )?,
+                _ => return Err(syn::Error::new(
+                    option.span(), 
+                    "unknown option, expected `mod` or `vis`",
+                )),
+            }
+        }
+
         Ok(Self { 
             span: path.span(),
             path: path.value(),
+            module: module.unwrap_or_else(|| 
+                syn::Ident::new("localisation", proc_macro2::Span::call_site())
+            ),
+            visibility: visibility.unwrap_or(syn::Visibility::Inherited),
         })
     }
 }
+
+/// Sets an option, unless it was already set.
+fn set_option<T>(
+    option: &mut Option<T>, 
+    value: T, 
+    span: proc_macro2::Span,
+) -> syn::Result<()> {
+    if option.is_some() {
+        return Err(syn::Error::new(span, "option given more than once"));
+    }
+    *option = Some(value);
+    Ok(())
+}
 impl Loader {
     pub fn collect(self) -> syn::Result<LoadedData> {
-        let parsed = Parser::new(self.path).map(Parser::parse);
+        let parsed = Parser::new(self.resolve_path())
+        .map_err(|e| vec![e])
+        .and_then(Parser::parse);
 
-        let ParsedData { locales, keys } = match parsed {
-            Ok(Ok(pd)) => pd,
-            Ok(Err(e)) | Err(e) => return Err(syn::Error::new(
-                self.span, 
-                e, 
-            )),
+        let ParsedData { 
+            locales, 
+            native_names, 
+            directions, 
+            keys, 
+            paths, 
+            warnings,
+            release_errors,
+        } = match parsed {
+            Ok(pd) => pd,
+            Err(errors) => return Err(self.combine_errors(errors)),
         };
 
+        let warnings = warnings
+        .iter()
+        .map(safflower_core::error::Error::warning)
+        .collect();
+        let release_errors = release_errors
+        .iter()
+        .map(|e| (e.warning(), e.to_string()))
+        .collect();
+
+        // `include_bytes!` resolves relative paths from the invoking source
+        // file, not from where the files were read, so these must be 
+        // absolute. They were all just read, so they exist.
+        let paths = paths
+        .into_iter()
+        .map(|path| path.canonicalize().unwrap_or(path))
+        .collect();
+
         Ok(LoadedData {
+            module: self.module,
+            visibility: self.visibility,
             locales,
+            native_names,
+            directions,
             keys,
+            paths,
+            warnings,
+            release_errors,
         })
     }
+
+    /// Turns every error into one that becomes its own `compile_error!`.
+    fn combine_errors(
+        &self, 
+        errors: Vec<safflower_core::error::Error>,
+    ) -> syn::Error {
+        let count = errors.len();
+
+        let mut errors = errors
+        .into_iter()
+        .take(MAX_ERRORS)
+        .map(|e| syn::Error::new(self.span, e));
+
+        // There is always at least one error
+        let mut combined = errors.next().expect("no errors to combine");
+        for e in errors { combined.combine(e); }
+        
+        if count > MAX_ERRORS {
+            combined.combine(syn::Error::new(
+                self.span, 
+                format!("{} more errors not shown", count - MAX_ERRORS),
+            ));
+        }
+
+        combined
+    }
+
+    /// Resolves the path relative to the invoking crate's manifest 
+    /// directory, like `include_str!` would, rather than wherever rustc 
+    /// happens to be running.
+    fn resolve_path(&self) -> PathBuf {
+        // Joining an absolute path replaces the root, so those still work
+        std::env::var_os("CARGO_MANIFEST_DIR").map_or_else(
+            || PathBuf::from(&self.path), 
+            |root| PathBuf::from(root).join(&self.path),
+        )
+    }
 }
 
 pub struct LoadedData {
+    module: syn::Ident,
+    visibility: syn::Visibility,
     locales: Vec<Name>,
+    native_names: Option<Vec<String>>,
+    directions: Vec<Direction>,
     keys: Vec<Key>,
+    paths: Vec<PathBuf>,
+    warnings: Vec<String>,
+    /// Warnings that are errors in release builds, written as each.
+    release_errors: Vec<(String, String)>,
 }
 impl quote::ToTokens for LoadedData {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let generator = Generator::new(
             self.locales.clone(), 
             self.keys.clone(),
-        );
+        )
+        .native_names(self.native_names.clone())
+        .directions(self.directions.clone());
 
         let code = generator.generate();
 
-        tokens.extend(quote! { mod localisation { #code } });
+        // Including the files makes cargo aware of them, so that it rebuilds
+        // whenever any of them change.
+        let dependencies = self.paths
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned());
+
+        let warnings = self.warnings.iter().map(warning);
+        let release_errors = self.release_errors.iter().map(|(warn, error)| {
+            let warning = warning(warn);
+            quote! {
+                #[cfg(debug_assertions)]
+                #warning
+                #[cfg(not(debug_assertions))]
+                compile_error!(#error);
+            }
+        });
+
+        let Self { module, visibility, .. } = self;
+
+        tokens.extend(quote! { 
+            #visibility mod #module { 
+                #(const _: &[u8] = include_bytes!(#dependencies);)*
+                #(#warnings)*
+                #(#release_errors)*
+                #code 
+            } 
+        });
+    }
+}
+
+/// There is no stable way for a proc macro to emit a warning, but using 
+/// something deprecated does, with the note as its message.
+fn warning(message: &String) -> proc_macro2::TokenStream {
+    quote! {
+        const _: () = {
+            #[deprecated(note = #message)]
+            #[allow(non_upper_case_globals)]
+            const safflower_warning: () = ();
+            safflower_warning
+        };
     }
 }
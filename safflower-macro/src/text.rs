@@ -1,47 +1,193 @@
-use safflower_core::LOCALE_FAILURE_MESSAGE;
+use proc_macro2::TokenStream;
+use quote::quote;
+use safflower_core::name::Name;
 
 pub struct Texter {
+    locale: Option<syn::Expr>,
+    /// The path to the module made by `load!`.
+    module: syn::Path,
     key: syn::Ident,
-    args: Vec<syn::Expr>
+    args: Vec<syn::Expr>,
+    named: Vec<(syn::Ident, syn::Expr)>,
+    lazy: bool,
+}
+impl Texter {
+    /// Makes it give a value that formats the text when displayed, instead 
+    /// of a formatted `String`.
+    pub const fn lazy(mut self) -> Self {
+        self.lazy = true;
+        self
+    }
+
+    /// The locale given, or else the current one.
+    fn locale(&self) -> TokenStream {
+        self.locale.as_ref().map_or_else(
+            || {
+                let module = &self.module;
+                quote! { #module::get_locale() }
+            }, 
+            |locale| quote! { #locale },
+        )
+    }
+
+    /// Gives a value that formats the text when displayed.
+    pub fn display(&self) -> TokenStream {
+        if !self.named.is_empty() { return self.named_display(); }
+
+        let Self { module, key, args, .. } = &self;
+        let locale = self.locale();
+
+        quote! {
+            #module::display::#key(
+                #locale
+                #(,&(#args))*
+            )
+        }
+    }
+
+    /// Builds the key's display type with its fields named, so that the 
+    /// compiler points out any argument that is misspelt, missing, or extra.
+    fn named_display(&self) -> TokenStream {
+        let Self { module, key, .. } = &self;
+        let locale = self.locale();
+        let (names, args): (Vec<_>, Vec<_>) = self.named
+        .iter()
+        .map(|(name, arg)| (name, arg))
+        .unzip();
+
+        // The key was a valid identifier, so it is a valid name as well
+        let type_name = Name::try_from(key.to_string().as_str())
+            .map_or_else(|_| key.to_string(), |name| name.type_name());
+        let type_name = syn::Ident::new(&type_name, key.span());
+
+        quote! {
+            #module::display::#type_name {
+                locale: #locale,
+                #(#names: &(#args),)*
+            }
+        }
+    }
 }
 impl syn::parse::Parse for Texter {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         // Parse pattern:
-        // IDENT (, EXPR)*
+        // (in EXPR,)? PATH ((, EXPR)* | (, IDENT = EXPR)*)
+
+        // Gets the optional locale
+        let locale = if input.peek(syn::Token![in]) {
+            _ = input.parse::<syn::Token![in]>()?;
+            let locale = input.parse()?;
+            _ = input.parse::<syn::Token![,]>()?;
+            Some(locale)
+        } else {
+            None
+        };
+
+        // Gets PATH, where the last segment is the key and the rest is the 
+        // module it is in
+        let mut module = input.call(syn::Path::parse_mod_style)?;
+        let key = module
+            .segments
+            .pop()
+            .map(syn::punctuated::Pair::into_value)
+            .ok_or_else(|| input.error("expected a key"))?
+            .ident;
 
-        // Gets IDENT
-        let key = input.parse()?;
+        if module.segments.is_empty() {
+            if module.leading_colon.is_some() {
+                return Err(input.error("expected a module before the key"));
+            }
+            module = syn::Ident::new(
+                "localisation", 
+                proc_macro2::Span::call_site(),
+            ).into();
+        } else {
+            // Popping the key leaves a trailing `::` behind
+            _ = module.segments.pop_punct();
+        }
 
-        // Gets any number of (, EXPR)
+        // Gets any number of (, EXPR) or (, IDENT = EXPR)
         let mut args = Vec::new();
-        while let Ok(arg) = parse_arg(input) {
-            args.push(arg);
+        let mut named = Vec::new();
+        while input.parse::<syn::Token![,]>().is_ok() {
+            if input.peek(syn::Ident) 
+            && input.peek2(syn::Token![=]) 
+            && !input.peek2(syn::Token![==]) {
+                let name = input.parse()?;
+                _ = input.parse::<syn::Token![=]>()?;
+                named.push((name, input.parse()?));
+            } else if let Ok(arg) = input.parse() {
+                args.push(arg);
+            }
+        }
+
+        if !args.is_empty() && !named.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &args[0], 
+                "cannot mix positional and named arguments; \
+                positional ones may be named `arg0`, `arg1`, and so on",
+            ));
         }
         
         Ok(Self {
+            locale,
+            module,
             key,
             args,
+            named,
+            lazy: false,
         })
     }
 }
 impl quote::ToTokens for Texter {
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        let Self { key, args } = &self;
-
-        let new = quote::quote! {
-            localisation::#key(
-                *localisation::LOCALE
-                    .lock()
-                    .expect(#LOCALE_FAILURE_MESSAGE)
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if self.lazy {
+            tokens.extend(self.display());
+            return;
+        }
+
+        if !self.named.is_empty() {
+            let display = self.named_display();
+            tokens.extend(quote! { ::std::string::ToString::to_string(&#display) });
+            return;
+        }
+
+        let Self { module, key, args, .. } = &self;
+        let locale = self.locale();
+
+        tokens.extend(quote! {
+            #module::#key(
+                #locale
                 #(,#args)*
             )
-        };
+        });
+    }
+}
 
-        tokens.extend(new);
+/// A destination to write to, followed by the same as for [`Texter`].
+pub struct Writer {
+    destination: syn::Expr,
+    texter: Texter,
+}
+impl syn::parse::Parse for Writer {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        // Parse pattern:
+        // EXPR, TEXTER
+        let destination = input.parse()?;
+        _ = input.parse::<syn::Token![,]>()?;
+        let texter = input.parse()?;
+
+        Ok(Self {
+            destination,
+            texter,
+        })
     }
 }
+impl quote::ToTokens for Writer {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let destination = &self.destination;
+        let display = self.texter.display();
 
-fn parse_arg(input: syn::parse::ParseStream) -> syn::Result<syn::Expr> {
-    _ = input.parse::<syn::Token![,]>()?;
-    input.parse()
+        tokens.extend(quote! { write!(#destination, "{}", #display) });
+    }
 }
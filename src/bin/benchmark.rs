@@ -1,39 +1,105 @@
 use safflower::{load, text};
 // use safflower_core as core;
 
+load!("src/bin/lorem256_1024.txt");
+
 fn main() {
     loading_formatting_noargs();
+    loading_formatting_args();
+    locale_access();
 }
 
 #[allow(clippy::useless_format)]
 fn loading_formatting_noargs() {
-    load!("src/bin/lorem256_1024.txt");
-    
     let n = 1_000_000;
 
     let t0 = std::time::Instant::now();
     for _ in 0..n {
-        _ = format!(
+        _ = std::hint::black_box(format!(
             "Lorem ipsum dolor sit amet, consectetur adipiscing elit. Nunc \
             eget metus dapibus, hendrerit libero et, posuere massa. Sed eget \
             odio magna. Suspendisse potenti. In id tellus semper enim \
             molestie ornare. Donec semper sapien non luctus lobortis. \
             Praesent est."
-        );
+        ));
     }
     let time_format = t0.elapsed();
 
+    // Keys without arguments give a `&'static str`, so this is only a lookup
     let t0 = std::time::Instant::now();
     for _ in 0..n {
-        _ = text!(line0);
+        _ = std::hint::black_box(text!(line0));
+    }
+    let time_text = t0.elapsed();
+
+    report("format!s", time_format, "text!s", time_text, n);
+}
+
+fn loading_formatting_args() {
+    let n = 1_000_000;
+
+    let t0 = std::time::Instant::now();
+    for i in 0..n {
+        let dolor = std::hint::black_box("dolor");
+        let count = std::hint::black_box(i);
+        _ = std::hint::black_box(format!(
+            "Lorem ipsum {dolor} sit amet, consectetur adipiscing elit. Nunc \
+            eget metus dapibus, hendrerit libero et, posuere massa. Sed eget \
+            odio magna. Suspendisse potenti. In id tellus semper enim \
+            molestie ornare. Donec semper sapien non luctus lobortis. \
+            Praesent {count} est."
+        ));
+    }
+    let time_format = t0.elapsed();
+
+    let t0 = std::time::Instant::now();
+    for i in 0..n {
+        let dolor = std::hint::black_box("dolor");
+        let count = std::hint::black_box(i);
+        _ = std::hint::black_box(text!(args, dolor, count));
     }
     let time_text = t0.elapsed();
 
+    report("format!s", time_format, "text!s", time_text, n);
+}
+
+/// Compares getting the current locale to locking a `Mutex` for it, which is
+/// how it used to be kept.
+fn locale_access() {
+    static MUTEX: std::sync::Mutex<localisation::Locale> = 
+        std::sync::Mutex::new(localisation::Locale::En);
+
+    let n = 1_000_000;
+
+    let t0 = std::time::Instant::now();
+    for _ in 0..n {
+        _ = std::hint::black_box(*MUTEX.lock().unwrap());
+    }
+    let time_mutex = t0.elapsed();
+
+    let t0 = std::time::Instant::now();
+    for _ in 0..n {
+        _ = std::hint::black_box(localisation::get_locale());
+    }
+    let time_atomic = t0.elapsed();
+
+    report("Mutex locks", time_mutex, "get_locale()s", time_atomic, n);
+}
+
+/// Prints how long two ways of doing the same took, the second relative to 
+/// the first.
+fn report(
+    base: &str, 
+    base_time: std::time::Duration, 
+    other: &str, 
+    other_time: std::time::Duration, 
+    n: u32,
+) {
     println!(
-        "{n} format!s took {:.2} ms\n\
-         {n} text!s   took {:.2} ms = {:.1} %",
-        time_format.as_secs_f32()*1000.0,
-        time_text.as_secs_f32()*1000.0,
-        time_text.as_secs_f32() / time_format.as_secs_f32() * 100.
+        "{n} {base:<13} took {:.2} ms\n\
+         {n} {other:<13} took {:.2} ms = {:.1} %",
+        base_time.as_secs_f32()*1000.0,
+        other_time.as_secs_f32()*1000.0,
+        other_time.as_secs_f32() / base_time.as_secs_f32() * 100.
     );
 }
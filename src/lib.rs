@@ -3,4 +3,14 @@
 #[cfg(test)]
 mod tests;
 
-pub use safflower_macro::{load, text};
+pub use safflower_macro::{
+    load, 
+    text, 
+    lazy_text, 
+    write_text, 
+    print_text, 
+    println_text, 
+    eprint_text, 
+    eprintln_text, 
+    panic_text,
+};
@@ -1,6 +1,16 @@
 use core::f32;
 
-use safflower_macro::{load, text};
+use safflower_macro::{
+    load, 
+    text, 
+    lazy_text, 
+    write_text, 
+    print_text, 
+    println_text, 
+    eprint_text, 
+    eprintln_text, 
+    panic_text,
+};
 
 #[test]
 fn absolute_minimal() {
@@ -116,3 +126,432 @@ fn separate_files_and_locales() {
     assert_eq!(text!(key1), "B");
     assert_eq!(text!(key2), "B");
 }
+
+#[test]
+fn reordered_arguments() {
+    load!("test-data/reordered_args.txt");
+    let a = 'A';
+    let b = 'B';
+
+    assert_eq!(text!(order, a, b), "A then B");
+    localisation::set_locale(localisation::Locale::De);
+    assert_eq!(text!(order, a, b), "B dann A");
+}
+
+#[test]
+#[allow(deprecated)]
+fn unused_arguments() {
+    load!("test-data/unused_args.txt");
+
+    let locale = localisation::Locale::En;
+    assert_eq!(localisation::count(locale, 1, 2), "1 of 2");
+    let locale = localisation::Locale::De;
+    assert_eq!(localisation::count(locale, 1, 2), "2");
+}
+
+#[test]
+fn escaped_braces() {
+    load!("test-data/braces.txt");
+    let name = "Tester";
+
+    assert_eq!(text!(json, name), "{\"name\": \"Tester\"}");
+    assert_eq!(text!(code), "fn main() { println!(\"{}\"); }");
+}
+
+#[test]
+fn escape_sequences() {
+    load!("test-data/escapes.txt");
+    let name = "x";
+
+    assert_eq!(text!(lines), "one\ntwo\tthree");
+    assert_eq!(text!(space), "100\u{a0}km");
+    assert_eq!(text!(quoted, name), "\"x\" \\ {x}");
+}
+
+#[test]
+fn block_value() {
+    load!("test-data/block.txt");
+
+    assert_eq!(
+        text!(help), 
+        "Usage: tool [OPTIONS]\n\nOptions:\n    -h  Show this text",
+    );
+}
+
+#[test]
+fn no_arguments_static() {
+    load!("test-data/greet_en_se.txt");
+
+    let greeting: &'static str = text!(greet);
+    assert_eq!(greeting, "Hi!");
+}
+
+#[test]
+fn lazy_display() {
+    use std::fmt::Write;
+    load!("test-data/greet_name_en_it.txt");
+    let name = String::from("Tester");
+
+    let mut buffer = String::new();
+    write!(buffer, "{}", lazy_text!(greet, name)).unwrap();
+    assert_eq!(buffer, "Hi Tester!");
+
+    let lazy = localisation::display::greet(localisation::Locale::It, "Tester");
+    assert_eq!(lazy.to_string(), "Ciao Tester!");
+    assert_eq!(name, "Tester");
+}
+
+#[test]
+fn argument_named_f() {
+    load!("test-data/formatter_arg.txt");
+    let f = 2.25;
+
+    assert_eq!(text!(scaled, f), "2.25x as fast, 2.2x");
+    assert_eq!(lazy_text!(scaled, f).to_string(), "2.25x as fast, 2.2x");
+}
+
+#[test]
+fn write_family() {
+    use std::fmt::Write;
+    load!("test-data/greet_name_en_it.txt");
+    let name = "Tester";
+
+    let mut buffer = String::new();
+    write_text!(buffer, greet, name).unwrap();
+    write_text!(&mut buffer, in localisation::Locale::It, greet, name).unwrap();
+    assert_eq!(buffer, "Hi Tester!Ciao Tester!");
+
+    let mut bytes = Vec::new();
+    {
+        use std::io::Write;
+        write_text!(bytes, greet, name).unwrap();
+    }
+    assert_eq!(bytes, b"Hi Tester!");
+
+    print_text!(greet, name);
+    println_text!(greet, name);
+    eprint_text!(greet, name);
+    eprintln_text!(in localisation::Locale::It, greet, name);
+}
+
+#[test]
+#[should_panic(expected = "Hi Tester!")]
+fn panic_with_text() {
+    load!("test-data/greet_name_en_it.txt");
+    let name = "Tester";
+
+    panic_text!(greet, name);
+}
+
+#[test]
+fn explicit_locale() {
+    load!("test-data/greet_name_en_it.txt");
+    let name = "Tester";
+    let user_locale = localisation::Locale::It;
+
+    assert_eq!(text!(in user_locale, greet, name), "Ciao Tester!");
+    assert_eq!(text!(in localisation::Locale::En, greet, name), "Hi Tester!");
+    assert_eq!(
+        lazy_text!(in user_locale, greet, name).to_string(), 
+        "Ciao Tester!",
+    );
+
+    // The current locale is left alone
+    assert_eq!(localisation::get_locale(), localisation::Locale::En);
+    assert_eq!(text!(greet, name), "Hi Tester!");
+}
+
+#[test]
+fn explicit_locale_no_arguments() {
+    load!("test-data/greet_en_se.txt");
+    let locales = localisation::LOCALES;
+
+    assert_eq!(text!(in locales[1], greet), "Hej!");
+}
+
+#[test]
+fn named_arguments() {
+    use std::fmt::Write;
+    load!("test-data/reordered_args.txt");
+    let pair = ('A', 'B');
+
+    assert_eq!(text!(order, b = pair.1, a = pair.0), "A then B");
+    assert_eq!(text!(in localisation::Locale::De, order, a = 1, b = 2), "2 dann 1");
+    assert_eq!(lazy_text!(order, a = "x", b = "y").to_string(), "x then y");
+
+    let mut buffer = String::new();
+    write_text!(buffer, order, a = 'a', b = 'b').unwrap();
+    assert_eq!(buffer, "a then b");
+}
+
+#[test]
+fn named_positional_arguments() {
+    load!("test-data/greet_name_en_it.txt");
+
+    assert_eq!(text!(greet, arg0 = "Tester"), "Hi Tester!");
+}
+
+mod tables {
+    use safflower_macro::load;
+
+    load!("test-data/greet_en_se.txt", mod = short, vis = pub);
+    load!("test-data/greet_name_en_it.txt", mod = named, vis = pub(crate));
+}
+
+#[test]
+fn module_options() {
+    load!("test-data/greet_en_se.txt", mod = plain);
+    load!("test-data/greet_name_en_it.txt", mod = with_name,);
+
+    assert_eq!(text!(plain::greet), "Hi!");
+    assert_eq!(text!(with_name::greet, "Tester"), "Hi Tester!");
+    assert_eq!(text!(in with_name::Locale::It, with_name::greet, "Tester"), "Ciao Tester!");
+}
+
+#[test]
+fn module_paths() {
+    let locale = tables::short::Locale::Se;
+
+    assert_eq!(text!(in locale, tables::short::greet), "Hej!");
+    assert_eq!(text!(crate::tests::tables::named::greet, "Tester"), "Hi Tester!");
+    assert_eq!(
+        lazy_text!(self::tables::named::greet, arg0 = "Tester").to_string(), 
+        "Hi Tester!",
+    );
+}
+
+#[test]
+fn scoped_locale() {
+    load!("test-data/greet_en_se.txt");
+    use localisation::Locale;
+
+    assert_eq!(localisation::with_locale(Locale::Se, || text!(greet)), "Hej!");
+    assert_eq!(text!(greet), "Hi!");
+
+    {
+        let _guard = localisation::override_locale(Locale::Se);
+        assert_eq!(text!(greet), "Hej!");
+
+        // Overrides nest, restoring the previous one when dropped
+        let inner = localisation::override_locale(Locale::En);
+        assert_eq!(text!(greet), "Hi!");
+        drop(inner);
+        assert_eq!(text!(greet), "Hej!");
+
+        // The global locale is only used without an override
+        localisation::set_locale(Locale::En);
+        assert_eq!(localisation::get_locale(), Locale::Se);
+    }
+    assert_eq!(localisation::get_locale(), Locale::En);
+}
+
+#[test]
+fn thread_locale() {
+    load!("test-data/greet_name_en_it.txt");
+    use localisation::Locale;
+
+    let _guard = localisation::override_locale(Locale::It);
+
+    let other = std::thread::spawn(|| text!(greet, "Tester"))
+    .join()
+    .unwrap();
+
+    assert_eq!(other, "Hi Tester!");
+    assert_eq!(text!(greet, "Tester"), "Ciao Tester!");
+}
+
+#[test]
+fn negotiate_locale() {
+    load!("test-data/regions.txt");
+    use localisation::Locale;
+
+    assert_eq!(Locale::negotiate(&["fr-CA"]), Locale::FrCa);
+    assert_eq!(Locale::negotiate(&["fr-BE"]), Locale::Fr);
+    assert_eq!(Locale::negotiate(&["DE-de"]), Locale::De);
+    assert_eq!(Locale::negotiate(&["de_AT.UTF-8"]), Locale::De);
+    assert_eq!(Locale::negotiate(&["fr_CA.UTF-8@euro"]), Locale::FrCa);
+
+    // Quality weights, in one header or as separate tags
+    assert_eq!(Locale::negotiate(&["it, de;q=0.5, fr;q=0.9"]), Locale::Fr);
+    assert_eq!(Locale::negotiate(&["de;q=0.5", "fr-CH;q=0.7"]), Locale::Fr);
+    assert_eq!(Locale::negotiate(&["fr;q=0", "de;q=0.1"]), Locale::De);
+
+    // Falls back to the first declared locale
+    assert_eq!(Locale::negotiate(&["it", "*"]), Locale::En);
+    assert_eq!(Locale::negotiate(&[]), Locale::En);
+    assert_eq!(Locale::negotiate(&["C"]), Locale::En);
+}
+
+#[test]
+fn locale_conversions() {
+    load!("test-data/regions.txt");
+    use localisation::{Locale, LOCALES, UnknownLocale};
+
+    assert_eq!(Locale::COUNT, 4);
+    assert_eq!(Locale::FrCa.code(), "fr-CA");
+    assert_eq!(Locale::FrCa.to_string(), "fr-CA");
+    assert_eq!(format!("[{:>6}]", Locale::De), "[    de]");
+
+    assert_eq!("fr-CA".parse(), Ok(Locale::FrCa));
+    assert_eq!("FR_ca".parse(), Ok(Locale::FrCa));
+    assert_eq!(Locale::try_from("DE"), Ok(Locale::De));
+    assert_eq!("fr-BE".parse::<Locale>(), Err(UnknownLocale("fr-BE".into())));
+
+    for (i, locale) in LOCALES.into_iter().enumerate() {
+        assert_eq!(locale.as_index(), i);
+        assert_eq!(Locale::from_index(i), Some(locale));
+        assert_eq!(locale.code().parse(), Ok(locale));
+    }
+    assert_eq!(Locale::from_index(Locale::COUNT), None);
+
+    assert_eq!(Locale::default(), Locale::En);
+    assert!(Locale::En < Locale::De);
+}
+
+#[test]
+fn locale_metadata() {
+    load!("test-data/metadata.txt");
+    use localisation::{Direction, Locale};
+
+    let names = localisation::LOCALES.map(Locale::native_name);
+    assert_eq!(names, ["English", "Italiano", "العربية"]);
+
+    assert_eq!(Locale::It.direction(), Direction::Ltr);
+    assert_eq!(Locale::Ar.direction(), Direction::Rtl);
+}
+
+#[test]
+#[allow(deprecated)]
+fn fallback_locales() {
+    load!("test-data/fallback.txt");
+    use localisation::Locale;
+
+    assert_eq!(text!(in Locale::DeCh, greet), "Grüezi!");
+    assert_eq!(text!(in Locale::DeCh, farewell), "Tschüss!");
+    assert_eq!(text!(in Locale::DeCh, thanks), "Thanks!");
+    assert_eq!(text!(in Locale::De, thanks), "Thanks!");
+
+    // Plurals keep the rules of the locale they were written for
+    assert_eq!(text!(in Locale::Pl, files, 1), "1 file");
+    assert_eq!(text!(in Locale::Pl, files, 22), "22 files");
+}
+
+#[test]
+fn regional_inheritance() {
+    load!("test-data/regional.txt");
+    use localisation::Locale;
+
+    assert_eq!(text!(in Locale::En, colour), "color");
+    assert_eq!(text!(in Locale::EnGb, colour), "colour");
+    assert_eq!(text!(in Locale::EnAu, colour), "colour");
+
+    assert_eq!(text!(in Locale::EnGb, greet, name = "Tester"), "Hi Tester!");
+    assert_eq!(text!(in Locale::EnAu, greet, name = "Tester"), "G'day Tester!");
+}
+
+#[test]
+fn plural_categories() {
+    load!("test-data/plurals.txt");
+    use localisation::Locale;
+
+    assert_eq!(text!(in Locale::En, files, 0, "docs"), "No files in docs");
+    assert_eq!(text!(in Locale::En, files, 1, "docs"), "1 file in docs");
+    assert_eq!(text!(in Locale::En, files, 2_u8, "docs"), "2 files in docs");
+
+    for (count, text) in [
+        (1, "1 plik w docs"),
+        (3, "3 pliki w docs"),
+        (5, "5 plików w docs"),
+        (12, "12 plików w docs"),
+        (22, "22 pliki w docs"),
+    ] {
+        assert_eq!(text!(in Locale::Pl, files, count, "docs"), text);
+    }
+
+    assert_eq!(text!(in Locale::Ar, files, 2, "docs"), "ملفان في docs");
+    assert_eq!(text!(in Locale::Ar, files, 11, "docs"), "11 ملفًا في docs");
+
+    assert_eq!(text!(in Locale::En, unread, -1), "You have a message");
+    assert_eq!(text!(in Locale::En, unread, 7), "You have   7 messages");
+    assert_eq!(
+        lazy_text!(in Locale::En, files, count = 4, dir = "docs").to_string(),
+        "4 files in docs",
+    );
+}
+
+#[test]
+fn ordinal_categories() {
+    load!("test-data/ordinals.txt");
+    use localisation::Locale;
+
+    for (n, text) in [
+        (1, "You came 1st"),
+        (2, "You came 2nd"),
+        (3, "You came 3rd"),
+        (4, "You came 4th"),
+        (11, "You came 11th"),
+        (12, "You came 12th"),
+        (21, "You came 21st"),
+        (102, "You came 102nd"),
+    ] {
+        assert_eq!(text!(in Locale::En, place, n), text);
+    }
+
+    assert_eq!(text!(in Locale::Fr, place, 1), "Vous êtes arrivé 1er");
+    assert_eq!(text!(in Locale::Fr, place, 2), "Vous êtes arrivé 2e");
+    assert_eq!(text!(in Locale::It, place, 8), "Sei arrivato all'8º posto");
+    assert_eq!(text!(in Locale::It, place, 2), "Sei arrivato al 2º posto");
+    assert_eq!(text!(in Locale::Es, place, 2), "Llegaste en 2º lugar");
+
+    assert_eq!(text!(in Locale::En, lap, 2, 1), "2nd of 1 lap");
+    assert_eq!(text!(in Locale::En, lap, 3, 10), "3rd of 10 laps");
+    assert_eq!(text!(in Locale::Fr, lap, 1, 10), "1er tour sur 10");
+}
+
+#[test]
+fn select_branches() {
+    load!("test-data/select.txt");
+    use localisation::{Locale, select::Select};
+
+    enum Gender { Female, Male, Other }
+    impl Select for Gender {
+        fn select(&self) -> &str {
+            match self {
+                Self::Female => "female",
+                Self::Male => "male",
+                Self::Other => "other",
+            }
+        }
+    }
+
+    assert_eq!(text!(in Locale::En, liked, Gender::Female, 1), "She likes 1 post");
+    assert_eq!(text!(in Locale::En, liked, Gender::Male, 2), "He likes 2 posts");
+    assert_eq!(text!(in Locale::En, liked, Gender::Other, 2), "They like 2 posts");
+    assert_eq!(text!(in Locale::Fr, liked, "male", 1), "Il aime 1 publication");
+    assert_eq!(text!(in Locale::Fr, liked, "female", 0), "Elle aime 0 publication");
+
+    assert_eq!(text!(in Locale::En, role, "admin"), "Administrator");
+    assert_eq!(text!(in Locale::En, role, String::from("editor")), "Role: editor");
+    assert_eq!(text!(in Locale::Fr, role, "editor"), "Rôle : editor");
+}
+
+#[test]
+fn icu_syntax() {
+    load!("test-data/icu.txt");
+    use localisation::Locale;
+
+    assert_eq!(text!(in Locale::En, inbox, 0, "Ann"), "Your inbox is empty");
+    assert_eq!(
+        text!(in Locale::En, inbox, 1, "Ann"), 
+        "You have one message from Ann",
+    );
+    assert_eq!(
+        text!(in Locale::De, inbox, 5, "Ann"), 
+        "Du hast 5 Nachrichten, die neueste von Ann",
+    );
+
+    assert_eq!(text!(in Locale::En, quoted, "x"), "It's {x}, not x's");
+
+    assert_eq!(text!(in Locale::En, nested, "female", 1), "She has #1 item");
+    assert_eq!(text!(in Locale::En, nested, "male", 2), "They have 2 items");
+}